@@ -0,0 +1,58 @@
+//! Editor quick-fixes for matches.
+
+use crate::types::Match;
+use std::ops::Range;
+
+/// A quick-fix replacing part of the checked text,
+/// see [`to_code_action`](Match::to_code_action).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeAction {
+    /// A human-readable title, e.g. `Replace 'their' with 'there'`.
+    pub title: String,
+    /// The byte range to replace in the checked text.
+    pub range: Range<usize>,
+    /// The replacement text.
+    pub replacement: String,
+}
+
+impl Match {
+    /// One code action per suggested replacement, in the API's order.
+    ///
+    /// Returns no actions if the match doesn't fit `text`.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "I can't remember how to go their.";
+    /// let response = client.check(text)?;
+    /// for action in response.matches[0].to_code_action(text) {
+    ///     println!("{}", action.title);
+    /// }
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn to_code_action(&self, text: &str) -> Vec<CodeAction> {
+        let range = match self.byte_range(text) {
+            Some(range) => range,
+            None => return Vec::new(),
+        };
+        let original = &text[range.clone()];
+        self.replacements
+            .iter()
+            .map(|replacement| {
+                let value = &replacement.value;
+                let title = if original.is_empty() {
+                    format!("Insert '{}'", value)
+                } else if value.is_empty() {
+                    format!("Remove '{}'", original)
+                } else {
+                    format!("Replace '{}' with '{}'", original, value)
+                };
+                CodeAction {
+                    title,
+                    range: range.clone(),
+                    replacement: value.clone(),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,224 @@
+//! Splitting long texts into chunks and merging their responses.
+
+use crate::{offset, Response};
+use std::ops::Range;
+
+/// How [`check_chunked`](crate::Client::check_chunked) splits texts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChunkStrategy {
+    /// Pack whole sentences into each chunk. This is the default.
+    #[default]
+    Sentence,
+    /// Like `Sentence`, but start each chunk with the last `n` sentences
+    /// of the previous one, so that checks spanning sentences still work
+    /// at chunk edges. Matches found twice in the overlap are reported once.
+    ///
+    /// Overlapping sentences count towards the chunk size, and are dropped
+    /// if they don't fit along with the next sentence.
+    SentenceWithOverlap(usize),
+}
+
+/// Splits texts into sentences for chunking.
+///
+/// See [`check_with_custom_sentence_splitter`](crate::Client::check_with_custom_sentence_splitter).
+pub trait TextSplitter {
+    /// Split `text` into sentences, as byte ranges.
+    ///
+    /// The ranges must be in order, on character boundaries,
+    /// and together cover the whole text.
+    fn split(&self, text: &str) -> Vec<Range<usize>>;
+}
+
+/// The default splitter: a sentence ends at a line break, or at `.`, `!`
+/// or `?` followed by whitespace or the end of the text.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SentenceSplitter;
+
+impl TextSplitter for SentenceSplitter {
+    fn split(&self, text: &str) -> Vec<Range<usize>> {
+        sentences(text)
+    }
+}
+
+/// A splitter for Chinese and Japanese text, which is usually written
+/// without spaces between sentences.
+///
+/// A sentence ends at a line break, at the full-width `。`, `！`, `？` or `．`
+/// (and the Western `.`, `!` and `?` if followed by whitespace), along
+/// with any closing quotes or brackets and whitespace after it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CjkSplitter;
+
+impl TextSplitter for CjkSplitter {
+    fn split(&self, text: &str) -> Vec<Range<usize>> {
+        const CLOSING: &[char] = &['」', '』', '）', '】', '〉', '》', '”', '’', '"', '\'', ')'];
+        let mut sentences = Vec::new();
+        let mut start = 0;
+        let mut chars = text.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            let ends = c == '\n'
+                || ['。', '！', '？', '．'].contains(&c)
+                || (['.', '!', '?'].contains(&c)
+                    && !matches!(chars.peek(), Some(&(_, next)) if !next.is_whitespace()));
+            if !ends {
+                continue;
+            }
+            let mut end = index + c.len_utf8();
+            while let Some(&(index, next)) = chars.peek() {
+                if !next.is_whitespace() && !CLOSING.contains(&next) {
+                    break;
+                }
+                end = index + next.len_utf8();
+                chars.next();
+            }
+            sentences.push(start..end);
+            start = end;
+        }
+        if start < text.len() {
+            sentences.push(start..text.len());
+        }
+        sentences
+    }
+}
+
+/// Split `text` into byte ranges of at most `max_chunk` bytes.
+///
+/// Whole sentences, as split by `splitter`, are packed into chunks when
+/// possible; longer sentences are split at whitespace, or at any character
+/// boundary as a last resort. An empty text is a single empty chunk.
+pub(crate) fn chunks(
+    text: &str,
+    max_chunk: usize,
+    strategy: ChunkStrategy,
+    splitter: &dyn TextSplitter,
+) -> Vec<Range<usize>> {
+    let max_chunk = max_chunk.max(1);
+    let overlap = match strategy {
+        ChunkStrategy::Sentence => 0,
+        ChunkStrategy::SentenceWithOverlap(n) => n,
+    };
+    let pieces: Vec<_> = splitter
+        .split(text)
+        .into_iter()
+        .flat_map(|sentence| split_long(text, sentence, max_chunk))
+        .collect();
+    if pieces.is_empty() {
+        return std::iter::once(0..0).collect();
+    }
+
+    let mut chunks = Vec::new();
+    let mut first = 0;
+    loop {
+        let mut last = first + 1;
+        while last < pieces.len() && pieces[last].end - pieces[first].start <= max_chunk {
+            last += 1;
+        }
+        chunks.push(pieces[first].start..pieces[last - 1].end);
+        if last == pieces.len() {
+            return chunks;
+        }
+        let mut next = last.saturating_sub(overlap).max(first + 1);
+        while next < last && pieces[last].end - pieces[next].start > max_chunk {
+            next += 1;
+        }
+        first = next;
+    }
+}
+
+/// Split `text` into sentences, each including its trailing whitespace.
+///
+/// A sentence ends at a line break, or at `.`, `!` or `?`
+/// followed by whitespace or the end of the text.
+pub(crate) fn sentences(text: &str) -> Vec<Range<usize>> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let ends = c == '\n'
+            || (['.', '!', '?'].contains(&c)
+                && !matches!(chars.peek(), Some(&(_, next)) if !next.is_whitespace()));
+        if !ends {
+            continue;
+        }
+        let mut end = index + c.len_utf8();
+        while let Some(&(index, next)) = chars.peek() {
+            if !next.is_whitespace() {
+                break;
+            }
+            end = index + next.len_utf8();
+            chars.next();
+        }
+        sentences.push(start..end);
+        start = end;
+    }
+    if start < text.len() {
+        sentences.push(start..text.len());
+    }
+    sentences
+}
+
+/// Split `text` into paragraphs, separated by blank lines.
+///
+/// Paragraphs include their line breaks but not the blank lines between them.
+pub(crate) fn paragraphs(text: &str) -> Vec<Range<usize>> {
+    let mut paragraphs: Vec<Range<usize>> = Vec::new();
+    let mut start = 0;
+    let mut in_paragraph = false;
+    for line in text.split_inclusive('\n') {
+        let end = start + line.len();
+        let blank = line.trim().is_empty();
+        match paragraphs.last_mut() {
+            Some(paragraph) if in_paragraph && !blank => paragraph.end = end,
+            _ if !blank => paragraphs.push(start..end),
+            _ => {}
+        }
+        in_paragraph = !blank;
+        start = end;
+    }
+    paragraphs
+}
+
+/// Split a range of `text` into pieces of at most `max` bytes.
+fn split_long(text: &str, range: Range<usize>, max: usize) -> Vec<Range<usize>> {
+    let mut pieces = Vec::new();
+    let mut start = range.start;
+    while range.end - start > max {
+        let mut end = start + max;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == start {
+            end = start + text[start..].chars().next().map_or(1, char::len_utf8);
+        }
+        let split = text[start..end]
+            .rfind(char::is_whitespace)
+            .map(|index| start + index + text[start + index..].chars().next().unwrap().len_utf8())
+            .filter(|&split| split > start)
+            .unwrap_or(end);
+        pieces.push(start..split);
+        start = split;
+    }
+    pieces.push(start..range.end);
+    pieces
+}
+
+/// Shift the matches of a chunk's response to positions in the full text,
+/// given the chunk's byte offset.
+pub(crate) fn shift(response: &mut Response, text: &str, chunk_start: usize) {
+    let by = offset::byte_to_utf16(text, chunk_start) as u32;
+    for m in &mut response.matches {
+        m.offset += by;
+    }
+}
+
+/// Merge the shifted response of a later chunk into `merged`,
+/// skipping matches already found in an overlapping chunk.
+pub(crate) fn absorb(merged: &mut Response, response: Response) {
+    merged.warnings.incomplete_results |= response.warnings.incomplete_results;
+    merged.skipped_matches += response.skipped_matches;
+    for m in response.matches {
+        if !merged.matches.contains(&m) {
+            merged.matches.push(m);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Expanding English contractions before checking.
+
+use crate::offset::TextMap;
+
+/// Contractions expanded by [`expand_contractions`](crate::Client::expand_contractions).
+///
+/// Only unambiguous contractions are listed: `'s` (is, has, or possessive)
+/// and `'d` (had or would) are left alone, as are dialectal forms like
+/// `ain't`. Keys are lowercase; lookups ignore case, accept both `'` and
+/// `’` as the apostrophe, and keep the capitalization of the first letter.
+pub const CONTRACTIONS: &[(&str, &str)] = &[
+    ("can't", "cannot"),
+    ("won't", "will not"),
+    ("shan't", "shall not"),
+    ("isn't", "is not"),
+    ("aren't", "are not"),
+    ("wasn't", "was not"),
+    ("weren't", "were not"),
+    ("don't", "do not"),
+    ("doesn't", "does not"),
+    ("didn't", "did not"),
+    ("haven't", "have not"),
+    ("hasn't", "has not"),
+    ("hadn't", "had not"),
+    ("wouldn't", "would not"),
+    ("shouldn't", "should not"),
+    ("couldn't", "could not"),
+    ("mustn't", "must not"),
+    ("i'm", "i am"),
+    ("you're", "you are"),
+    ("we're", "we are"),
+    ("they're", "they are"),
+    ("i've", "i have"),
+    ("you've", "you have"),
+    ("we've", "we have"),
+    ("they've", "they have"),
+    ("i'll", "i will"),
+    ("you'll", "you will"),
+    ("he'll", "he will"),
+    ("she'll", "she will"),
+    ("we'll", "we will"),
+    ("they'll", "they will"),
+    ("let's", "let us"),
+];
+
+/// Expand the [`CONTRACTIONS`] in `text`.
+pub(crate) fn expand(text: &str) -> TextMap {
+    TextMap::rewrite(words(text), |word| {
+        let key = word.to_lowercase().replace('\u{2019}', "'");
+        match CONTRACTIONS
+            .iter()
+            .find(|(contraction, _)| *contraction == key)
+        {
+            Some((_, expansion)) if word.starts_with(char::is_uppercase) => {
+                let mut chars = expansion.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            Some((_, expansion)) => expansion.to_string(),
+            None => word.to_string(),
+        }
+    })
+}
+
+/// Split `text` into words (with apostrophes) and the runs between them.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '\'' || c == '\u{2019}';
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let kind = is_word(first);
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| is_word(c) != kind)
+            .map_or(rest.len(), |(index, _)| index);
+        let (piece, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(piece)
+    })
+}
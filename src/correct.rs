@@ -0,0 +1,61 @@
+//! Applying suggested replacements to the checked text.
+
+use crate::types::Match;
+use crate::Response;
+
+impl Response {
+    /// Apply the top replacement of every match to `text`.
+    ///
+    /// Matches are applied from the start of the text; a match overlapping
+    /// one already applied is skipped, as are matches without replacements.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "I can't remember how to go their.";
+    /// let response = client.check(text)?;
+    /// assert_eq!("I can't remember how to go there.", response.apply_corrections(text));
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn apply_corrections(&self, text: &str) -> String {
+        self.apply_corrections_selective(text, |_| true)
+    }
+
+    /// Like [`apply_corrections`](Self::apply_corrections), but only apply
+    /// matches for which `predicate` returns `true`.
+    ///
+    /// ```no_run
+    /// # use grammarbot::{Client, Severity};
+    /// # let client = Client::new("your_api_key");
+    /// # let text = "I can't remember how to go their.";
+    /// # let response = client.check(text)?;
+    /// let fixed = response.apply_corrections_selective(text, |m| m.severity() == Severity::Spelling);
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn apply_corrections_selective(
+        &self,
+        text: &str,
+        predicate: impl Fn(&Match) -> bool,
+    ) -> String {
+        let mut edits: Vec<_> = self
+            .matches
+            .iter()
+            .filter(|m| predicate(m))
+            .filter_map(|m| Some((m.byte_range(text)?, &m.replacements.first()?.value)))
+            .collect();
+        edits.sort_by_key(|(range, _)| (range.start, range.end));
+
+        let mut corrected = String::with_capacity(text.len());
+        let mut position = 0;
+        for (range, replacement) in edits {
+            if range.start < position {
+                continue;
+            }
+            corrected.push_str(&text[position..range.start]);
+            corrected.push_str(replacement);
+            position = range.end;
+        }
+        corrected.push_str(&text[position..]);
+        corrected
+    }
+}
@@ -0,0 +1,80 @@
+//! Checking texts in encodings other than UTF-8.
+
+use crate::{Client, Error, Response, Result};
+use encoding_rs::{CoderResult, Encoding};
+
+impl Client {
+    /// Check a text in the given encoding, e.g. `"windows-1252"`.
+    ///
+    /// `encoding` is a [WHATWG label](https://encoding.spec.whatwg.org/#names-and-labels);
+    /// unknown labels return [`Error::UnknownEncoding`]. Malformed sequences
+    /// are checked as U+FFFD replacement characters.
+    ///
+    /// Unlike other checks, the offset and length of each match are *byte*
+    /// positions in `bytes`, so the flagged span in the source encoding is
+    /// `bytes[offset..offset + length]`. Matches that don't fit the decoded
+    /// text can't be mapped to bytes, so they are dropped, and counted by
+    /// [`Response::skipped_matches`].
+    ///
+    /// Requires the `encoding` feature.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let bytes = b"I can\x92t remember how to go their.";
+    /// let response = client.check_encoded(bytes, "windows-1252")?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_encoded(&self, bytes: &[u8], encoding: &str) -> Result<Response> {
+        let encoding =
+            Encoding::for_label(encoding.as_bytes()).ok_or_else(|| Error::UnknownEncoding {
+                encoding: encoding.to_string(),
+            })?;
+        let (text, starts) = decode(bytes, encoding);
+        let source =
+            |index: usize| match starts.binary_search_by_key(&index, |&(decoded, _)| decoded) {
+                Ok(found) => starts[found].1,
+                Err(_) => bytes.len(),
+            };
+
+        let mut response = self.check(&text)?;
+        let before = response.matches.len();
+        response.matches.retain_mut(|m| match m.byte_range(&text) {
+            Some(range) => {
+                let (start, end) = (source(range.start), source(range.end));
+                m.offset = start as u32;
+                m.length = (end - start) as u32;
+                true
+            }
+            None => false,
+        });
+        response.skipped_matches += before - response.matches.len();
+        Ok(response)
+    }
+}
+
+/// Decode `bytes` byte by byte, recording where each character
+/// of the decoded text starts, as `(decoded, source)` byte offsets.
+fn decode(bytes: &[u8], encoding: &'static Encoding) -> (String, Vec<(usize, usize)>) {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut text = String::new();
+    let mut starts = Vec::new();
+    let mut start = 0;
+    for index in 0..=bytes.len() {
+        let last = index == bytes.len();
+        let input = if last { &[][..] } else { &bytes[index..=index] };
+        let before = text.len();
+        text.reserve(decoder.max_utf8_buffer_length(input.len()).unwrap_or(16));
+        let (result, _, _) = decoder.decode_to_string(input, &mut text, last);
+        debug_assert_eq!(CoderResult::InputEmpty, result);
+        if text.len() > before {
+            starts.extend(
+                text[before..]
+                    .char_indices()
+                    .map(|(decoded, _)| (before + decoded, start)),
+            );
+            start = (index + 1).min(bytes.len());
+        }
+    }
+    (text, starts)
+}
@@ -0,0 +1,33 @@
+//! Choosing between candidate languages for a text.
+
+use crate::Response;
+
+/// Scores the response of checking a text in a candidate language,
+/// for [`check_multi_language_with`](crate::Client::check_multi_language_with).
+///
+/// Lower scores are better: the text is most likely in the language
+/// whose response scores lowest.
+pub trait LanguageScorer {
+    /// The score of `response`.
+    fn score(&self, response: &Response) -> f64;
+}
+
+/// Score responses by their number of matches. This is the default.
+///
+/// A text checked in the wrong language is flagged all over,
+/// mostly for spelling, so the fewest matches usually means
+/// the right language.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FewestMatches;
+
+impl LanguageScorer for FewestMatches {
+    fn score(&self, response: &Response) -> f64 {
+        response.matches.len() as f64
+    }
+}
+
+impl<F: Fn(&Response) -> f64> LanguageScorer for F {
+    fn score(&self, response: &Response) -> f64 {
+        self(response)
+    }
+}
@@ -32,16 +32,55 @@
 
 extern crate reqwest;
 extern crate serde;
+extern crate serde_ignored;
+extern crate serde_json;
 extern crate snafu;
 
-use serde::Deserialize;
-use snafu::{ResultExt, Snafu};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt, Snafu};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::ops::{Index, Range};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+mod action;
+mod chunk;
+pub mod contractions;
+mod correct;
+#[cfg(feature = "encoding")]
+mod encoding;
+mod fnv;
+mod language;
+mod limit;
+#[cfg(feature = "nfc")]
+mod normalize;
+pub mod offset;
+mod options;
+mod report;
+pub mod retry;
+mod sanitize;
+mod segment;
+pub mod transport;
+
+pub use action::CodeAction;
+pub use chunk::{ChunkStrategy, CjkSplitter, SentenceSplitter, TextSplitter};
+pub use language::{FewestMatches, LanguageScorer};
+pub use limit::RateLimiter;
+pub use options::{CheckOptions, RuleParamStyle};
+use retry::RetryPolicy;
+pub use segment::Segment;
+use transport::{RawResponse, ReqwestTransport, Transport};
 
 /// All the entities used in responses from the API.
 pub mod types {
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Software {
         pub name: String,
@@ -49,16 +88,17 @@ pub mod types {
         pub api_version: u8,
         pub premium: bool,
         pub premium_hint: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
         pub status: String,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Warnings {
         pub incomplete_results: bool,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Language {
         pub name: String,
@@ -66,18 +106,54 @@ pub mod types {
         pub detected_language: DetectedLanguage,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct DetectedLanguage {
         pub name: String,
         pub code: String,
+        /// Detection confidence in `0.0..=1.0`, if the server reports one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub confidence: Option<f64>,
+    }
+
+    impl DetectedLanguage {
+        /// Confidence below which a detection is considered weak.
+        pub const CONFIDENCE_THRESHOLD: f64 = 0.5;
+
+        /// Whether the server was unsure about the detected language.
+        ///
+        /// A detection is weak if the reported `confidence` is below
+        /// [`CONFIDENCE_THRESHOLD`](Self::CONFIDENCE_THRESHOLD), or, when no
+        /// confidence is reported, if the code is a bare language (`"en"`)
+        /// without a region variant (`"en-US"`).
+        pub fn is_low_confidence(&self) -> bool {
+            match self.confidence {
+                Some(confidence) => confidence < Self::CONFIDENCE_THRESHOLD,
+                None => !self.code.contains('-'),
+            }
+        }
+    }
+
+    /// A language supported by the server, as listed by
+    /// [`languages`](crate::Client::languages).
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SupportedLanguage {
+        /// Language name. `"English (US)"`
+        pub name: String,
+        /// Bare language code. `"en"`
+        pub code: String,
+        /// Full language code. `"en-US"`
+        pub long_code: String,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Match {
         pub message: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
         pub short_message: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
         pub replacements: Vec<Replacement>,
         pub offset: u32,
         pub length: u32,
@@ -87,13 +163,172 @@ pub mod types {
         pub rule: Rule,
     }
 
-    #[derive(Debug, Deserialize)]
+    /// Matches are ordered and compared by their position, then by rule ID:
+    /// two matches of the same rule at the same span are equal.
+    impl PartialEq for Match {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other) == std::cmp::Ordering::Equal
+        }
+    }
+
+    impl Eq for Match {}
+
+    impl PartialOrd for Match {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Match {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            (self.offset, self.length, &self.rule.id).cmp(&(
+                other.offset,
+                other.length,
+                &other.rule.id,
+            ))
+        }
+    }
+
+    impl Match {
+        /// How serious the issue is, judged by its rule.
+        pub fn severity(&self) -> crate::Severity {
+            crate::Severity::of(&self.rule)
+        }
+
+        /// A concise label for the match: the short message,
+        /// or the full message if the short one is empty.
+        pub fn display_message(&self) -> &str {
+            if self.short_message.is_empty() {
+                &self.message
+            } else {
+                &self.short_message
+            }
+        }
+
+        /// The byte range of the flagged span in the checked `text`.
+        ///
+        /// A span starting or ending inside a character, between the two
+        /// UTF-16 code units of a surrogate pair, is widened to cover the
+        /// whole character, so the range is always valid for `text`.
+        ///
+        /// Returns `None` if the match doesn't fit `text`.
+        pub fn byte_range(&self, text: &str) -> Option<std::ops::Range<usize>> {
+            let end = (self.offset + self.length) as usize;
+            let start = crate::offset::utf16_to_byte_floor(text, self.offset as usize)?;
+            let end = crate::offset::utf16_to_byte_ceil(text, end)?;
+            Some(start..end)
+        }
+
+        /// The flagged span and the top replacement as an inline diff.
+        ///
+        /// The span is marked as `[-removed-]` and the replacement as
+        /// `{+inserted+}`, e.g. `[-their-]{+there+}`.
+        /// An empty replacement is shown as a deletion only, and
+        /// a zero-length span as an insertion only.
+        ///
+        /// Returns `None` if there are no replacements
+        /// or the match doesn't fit `text`.
+        pub fn replacement_diff(&self, text: &str) -> Option<String> {
+            let replacement = &self.replacements.first()?.value;
+            let original = &text[self.byte_range(text)?];
+            let mut diff = String::new();
+            if !original.is_empty() {
+                diff.push_str(&format!("[-{}-]", original));
+            }
+            if !replacement.is_empty() {
+                diff.push_str(&format!("{{+{}+}}", replacement));
+            }
+            Some(diff)
+        }
+
+        /// The line, column, byte range and UTF-16 range of the match
+        /// in the checked `text`, or `None` if it doesn't fit `text`.
+        ///
+        /// ```no_run
+        /// # use grammarbot::Client;
+        /// # let client = Client::new("your_api_key");
+        /// let text = "I can't remember\nhow to go their.";
+        /// let response = client.check(text)?;
+        /// let location = response.matches[0].location(text).unwrap();
+        /// assert_eq!((2, 11), (location.line, location.column));
+        /// # Ok::<(), grammarbot::Error>(())
+        /// ```
+        pub fn location(&self, text: &str) -> Option<crate::offset::Location> {
+            let byte_range = self.byte_range(text)?;
+            let (line, column) = crate::offset::LineIndex::new(text).line_col(byte_range.start);
+            let start = self.offset as usize;
+            Some(crate::offset::Location {
+                line: line as u32,
+                column: column as u32,
+                byte_range,
+                utf16_range: start..start + self.length as usize,
+            })
+        }
+
+        /// The match's sentence with the top replacement applied,
+        /// or the sentence unchanged if there are no replacements.
+        ///
+        /// The API doesn't give the match's offset in its sentence, so the
+        /// flagged text is found by its surroundings in the `context`.
+        /// If it can't be found, the sentence is returned unchanged.
+        pub fn suggested_sentence(&self) -> String {
+            let replacement = match self.replacements.first() {
+                Some(replacement) => &replacement.value,
+                None => return self.sentence.clone(),
+            };
+            let context = &self.context.text;
+            let offset = self.context.offset as usize;
+            let span = crate::offset::utf16_to_byte(context, offset).and_then(|start| {
+                let end = crate::offset::utf16_to_byte(context, offset + self.length as usize)?;
+                Some(start..end)
+            });
+            let span = match span {
+                Some(span) => span,
+                None => return self.sentence.clone(),
+            };
+            let flagged = &context[span.clone()];
+            let before = context[..span.start].trim_start_matches("...");
+            let sentence = &self.sentence;
+            let position = sentence
+                .match_indices(flagged)
+                .map(|(index, _)| index)
+                .find(|&index| {
+                    let head = &sentence[..index];
+                    head.ends_with(before) || before.ends_with(head)
+                })
+                .or_else(|| sentence.find(flagged));
+            match position {
+                Some(index) => format!(
+                    "{}{}{}",
+                    &sentence[..index],
+                    replacement,
+                    &sentence[index + flagged.len()..]
+                ),
+                None => sentence.clone(),
+            }
+        }
+
+        /// Remove repeated replacement values, keeping the first of each.
+        ///
+        /// Values are compared case-sensitively. Responses from
+        /// [`Client`](crate::Client) checks are already deduplicated.
+        pub fn dedup_replacements(&mut self) {
+            let mut seen = std::collections::HashSet::new();
+            self.replacements
+                .retain(|replacement| seen.insert(replacement.value.clone()));
+        }
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Replacement {
         pub value: String,
+        /// Confidence in the replacement in `0.0..=1.0`, if the server reports one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub confidence: Option<f64>,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Context {
         pub text: String,
@@ -101,13 +336,13 @@ pub mod types {
         pub length: u32,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Type {
         pub type_name: String,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Rule {
         pub id: String,
@@ -116,7 +351,32 @@ pub mod types {
         pub category: Category,
     }
 
-    #[derive(Debug, Deserialize)]
+    impl Rule {
+        /// Markers of premium-only rules, see [`is_premium`](Self::is_premium).
+        pub const PREMIUM_MARKERS: &'static [&'static str] = &["PREMIUM", "PICKY_PRO"];
+
+        /// Whether this looks like a rule only available to premium users.
+        ///
+        /// This is a best-effort guess: a rule is premium if its ID, its
+        /// category ID or its issue type contains one of the
+        /// [`PREMIUM_MARKERS`](Self::PREMIUM_MARKERS), ignoring case.
+        pub fn is_premium(&self) -> bool {
+            self.is_premium_with(Self::PREMIUM_MARKERS)
+        }
+
+        /// Like [`is_premium`](Self::is_premium), with custom markers.
+        pub fn is_premium_with(&self, markers: &[&str]) -> bool {
+            let fields = [&self.id, &self.category.id, &self.issue_type];
+            markers.iter().any(|marker| {
+                let marker = marker.to_uppercase();
+                fields
+                    .iter()
+                    .any(|field| field.to_uppercase().contains(&marker))
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Category {
         /// Category ID. `"TYPOS"`
@@ -127,20 +387,373 @@ pub mod types {
 }
 
 /// A typed representation of the JSON response.
-#[derive(Debug, Deserialize)]
+///
+/// Responses serialize back to the API's JSON format,
+/// leaving out empty optional fields.
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Response {
     pub software: types::Software,
     pub warnings: types::Warnings,
     pub language: types::Language,
     pub matches: Vec<types::Match>,
+    /// Set from the client's [`set_default_ignored_categories`](Client::set_default_ignored_categories).
+    #[serde(skip)]
+    ignored_categories: Vec<String>,
+    /// Set when parsing with [`lenient_matches`](Client::lenient_matches),
+    /// and by `check_encoded`.
+    #[serde(skip)]
+    skipped_matches: usize,
+    /// Set from the client's [`category_priority`](Client::category_priority).
+    #[serde(skip)]
+    category_priority: Vec<String>,
+}
+
+impl Response {
+    /// Consume the response, keeping only its matches.
+    pub fn into_matches(self) -> Vec<types::Match> {
+        self.matches
+    }
+
+    /// The number of malformed matches skipped in
+    /// [lenient](Client::lenient_matches) mode, or because they don't fit
+    /// the text checked with `check_encoded`.
+    pub fn skipped_matches(&self) -> usize {
+        self.skipped_matches
+    }
+
+    /// The matches outside the client's default ignored categories.
+    pub fn relevant_matches(&self) -> impl Iterator<Item = &types::Match> {
+        self.matches
+            .iter()
+            .filter(move |m| !self.ignored_categories.contains(&m.rule.category.id))
+    }
+
+    /// Whether there are no [relevant matches](Self::relevant_matches).
+    pub fn is_clean(&self) -> bool {
+        self.relevant_matches().next().is_none()
+    }
+
+    /// Whether any [relevant match](Self::relevant_matches) is at least
+    /// as severe as `min_severity`, e.g. to fail a CI gate on grammar and
+    /// spelling errors but not on style suggestions.
+    pub fn is_significant(&self, min_severity: Severity) -> bool {
+        self.relevant_matches()
+            .any(|m| m.severity() >= min_severity)
+    }
+
+    /// The most severe match, the earliest one on ties.
+    pub fn worst_match(&self) -> Option<&types::Match> {
+        self.worst_matches(1).into_iter().next()
+    }
+
+    /// Up to `n` matches, most severe first, then by offset.
+    pub fn worst_matches(&self, n: usize) -> Vec<&types::Match> {
+        let mut matches = self.matches_sorted_by_severity();
+        matches.truncate(n);
+        matches
+    }
+
+    /// All matches, ordered by the client's
+    /// [`category_priority`](Client::category_priority), then by offset.
+    ///
+    /// Matches in categories missing from the priority list come last.
+    pub fn sorted_by_priority(&self) -> Vec<&types::Match> {
+        let priority = |m: &types::Match| {
+            self.category_priority
+                .iter()
+                .position(|category| *category == m.rule.category.id)
+                .unwrap_or(self.category_priority.len())
+        };
+        let mut matches: Vec<_> = self.matches.iter().collect();
+        matches.sort_by_key(|m| (priority(m), m.offset));
+        matches
+    }
+
+    /// All matches, most severe first, then by offset.
+    pub fn matches_sorted_by_severity(&self) -> Vec<&types::Match> {
+        let mut matches: Vec<_> = self.matches.iter().collect();
+        matches.sort_by_key(|m| (std::cmp::Reverse(m.severity()), m.offset));
+        matches
+    }
+
+    /// The rules of the matches by ID, e.g. for a legend of the rules
+    /// triggered in a document. Each rule is taken from its first match.
+    pub fn rule_catalog(&self) -> BTreeMap<String, types::Rule> {
+        let mut catalog = BTreeMap::new();
+        for m in &self.matches {
+            catalog
+                .entry(m.rule.id.clone())
+                .or_insert_with(|| m.rule.clone());
+        }
+        catalog
+    }
+
+    /// A stable hash of the matches, ignoring the rest of the response.
+    ///
+    /// Only the rule ID, offset, length and replacements of each match
+    /// are hashed, in [match order](types::Match#impl-Ord-for-Match), so
+    /// two checks with the same findings hash equally even if metadata
+    /// like `software.premium_hint` or the server's match order differ.
+    ///
+    /// The hash is 64-bit FNV-1a, so it stays the same across platforms,
+    /// Rust releases and versions of this crate, and can be persisted.
+    pub fn content_hash(&self) -> u64 {
+        let mut matches: Vec<_> = self.matches.iter().collect();
+        matches.sort();
+        let mut hasher = fnv::Fnv1a::new();
+        for m in matches {
+            hasher.write_str(&m.rule.id);
+            hasher.write_u64(m.offset.into());
+            hasher.write_u64(m.length.into());
+            hasher.write_u64(m.replacements.len() as u64);
+            for replacement in &m.replacements {
+                hasher.write_str(&replacement.value);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// The number of matches for each rule ID.
+    pub fn counts_by_rule(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for m in &self.matches {
+            *counts.entry(m.rule.id.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// The number of matches for each category ID.
+    pub fn counts_by_category(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for m in &self.matches {
+            *counts.entry(m.rule.category.id.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// The category ID with the most matches, e.g. for a summary badge.
+    ///
+    /// Ties go to the category with the most severe match,
+    /// then to the first ID alphabetically.
+    pub fn worst_category(&self) -> Option<&str> {
+        let mut categories: HashMap<&str, (usize, Severity)> = HashMap::new();
+        for m in &self.matches {
+            let entry = categories
+                .entry(&m.rule.category.id)
+                .or_insert((0, Severity::Other));
+            entry.0 += 1;
+            entry.1 = entry.1.max(m.severity());
+        }
+        categories
+            .into_iter()
+            .max_by(|(a, a_stats), (b, b_stats)| a_stats.cmp(b_stats).then_with(|| b.cmp(a)))
+            .map(|(category, _)| category)
+    }
+
+    /// The number of matches of each severity, as
+    /// `[spelling, grammar, style, other]`, e.g. for a compact badge.
+    pub fn stats_by_severity(&self) -> [usize; 4] {
+        let mut stats = [0; 4];
+        for m in &self.matches {
+            let slot = match m.severity() {
+                Severity::Spelling => 0,
+                Severity::Grammar => 1,
+                Severity::Style => 2,
+                Severity::Other => 3,
+            };
+            stats[slot] += 1;
+        }
+        stats
+    }
+
+    /// The matches with at least one replacement, e.g. for "fix all".
+    pub fn only_fixable(&self) -> Vec<&types::Match> {
+        self.matches
+            .iter()
+            .filter(|m| !m.replacements.is_empty())
+            .collect()
+    }
+
+    /// The matches on each line of the checked `text`, as 1-based line
+    /// numbers in order, for line-oriented output.
+    ///
+    /// A match spanning several lines belongs to the line it starts on.
+    /// Lines without matches are left out, as are matches that don't fit `text`.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "I can't remember\nhow to go their.";
+    /// let response = client.check(text)?;
+    /// for (line, matches) in response.matches_by_line(text) {
+    ///     for m in matches {
+    ///         println!("{}: {}", line, m.message);
+    ///     }
+    /// }
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn matches_by_line(&self, text: &str) -> Vec<(usize, Vec<&types::Match>)> {
+        let lines = offset::LineIndex::new(text);
+        let mut located: Vec<_> = self
+            .matches
+            .iter()
+            .filter_map(|m| Some((lines.line_col(m.byte_range(text)?.start).0, m)))
+            .collect();
+        located.sort_by_key(|&(line, _)| line);
+        let mut by_line: Vec<(usize, Vec<&types::Match>)> = Vec::new();
+        for (line, m) in located {
+            match by_line.last_mut() {
+                Some((last, matches)) if *last == line => matches.push(m),
+                _ => by_line.push((line, vec![m])),
+            }
+        }
+        by_line
+    }
+
+    /// Drop matches whose best replacement has a confidence below `threshold`.
+    ///
+    /// Matches without any confidence data are kept;
+    /// see [`min_confidence_with`](Self::min_confidence_with) to drop them.
+    pub fn min_confidence(&mut self, threshold: f64) -> &mut Self {
+        self.min_confidence_with(threshold, true)
+    }
+
+    /// Like [`min_confidence`](Self::min_confidence),
+    /// but choosing whether to keep matches without any confidence data.
+    pub fn min_confidence_with(&mut self, threshold: f64, keep_unknown: bool) -> &mut Self {
+        self.matches.retain(|m| {
+            let best = m
+                .replacements
+                .iter()
+                .filter_map(|replacement| replacement.confidence)
+                .fold(None, |best: Option<f64>, confidence| {
+                    Some(best.map_or(confidence, |best| best.max(confidence)))
+                });
+            match best {
+                Some(best) => best >= threshold,
+                None => keep_unknown,
+            }
+        });
+        self
+    }
+}
+
+/// Index into the matches, so that `response[0]` is `response.matches[0]`.
+///
+/// # Panics
+///
+/// Panics if `index` is out of bounds, like slice indexing.
+impl Index<usize> for Response {
+    type Output = types::Match;
+
+    fn index(&self, index: usize) -> &types::Match {
+        &self.matches[index]
+    }
+}
+
+/// How serious a match is.
+///
+/// Severities are ordered, so that `Spelling > Grammar > Style > Other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Anything not covered by other severities, e.g. whitespace.
+    Other,
+    /// Style suggestions, e.g. redundancy or wordiness.
+    Style,
+    /// Grammar errors, including commonly confused words.
+    Grammar,
+    /// Misspelled words.
+    Spelling,
+}
+
+impl Severity {
+    /// The severity of a rule, from its issue type, then its category.
+    pub fn of(rule: &types::Rule) -> Self {
+        match rule.issue_type.as_str() {
+            "misspelling" => return Severity::Spelling,
+            "grammar" => return Severity::Grammar,
+            "style" | "register" | "locale-violation" => return Severity::Style,
+            _ => {}
+        }
+        match rule.category.id.as_str() {
+            "TYPOS" => Severity::Spelling,
+            "GRAMMAR" | "CONFUSED_WORDS" | "CASING" => Severity::Grammar,
+            "STYLE" | "REDUNDANCY" | "PLAIN_ENGLISH" => Severity::Style,
+            _ => Severity::Other,
+        }
+    }
+}
+
+/// The default `User-Agent`, `grammarbot-rs/<version>`.
+pub const USER_AGENT: &str = concat!("grammarbot-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Punctuation stripped from both ends of a flagged span before comparing
+/// it to the [ignored words](Client::ignore_words).
+pub const IGNORED_PUNCTUATION: &[char] = &[
+    '.', ',', ';', ':', '!', '?', '"', '\'', '(', ')', '[', ']', '{', '}', '\u{2018}', '\u{2019}',
+    '\u{201c}', '\u{201d}', '\u{ab}', '\u{bb}',
+];
+
+/// Details about how a request went, see
+/// [`check_with_meta`](Client::check_with_meta).
+#[derive(Debug, Clone)]
+pub struct RequestMeta {
+    /// The number of attempts made, including retries.
+    pub attempts: u32,
+    /// The time spent on all attempts, including delays between them.
+    pub elapsed: Duration,
+    /// The status of the final response.
+    pub status: reqwest::StatusCode,
+    /// The base URL that served the response: the client's base, or its
+    /// [`fallback_base`](Client::fallback_base) if the base failed.
+    pub base: reqwest::Url,
+}
+
+/// The language detected for a paragraph, see
+/// [`check_with_language_override_per_paragraph`](Client::check_with_language_override_per_paragraph).
+#[derive(Debug, Clone)]
+pub struct ParagraphLanguage {
+    /// The byte range of the paragraph in the checked text.
+    pub range: Range<usize>,
+    /// The language detected for the paragraph,
+    /// or the client's language if detection failed.
+    pub language: types::DetectedLanguage,
+    /// Whether detection failed, so the paragraph was checked
+    /// in the client's [`language`](Client::language) instead.
+    pub fallback: bool,
 }
 
 /// The primary way to interact with the API.
+///
+/// Clones share the transport, and so the connection pool,
+/// along with the retry policy, rate limiter and retry budget.
+#[derive(Clone)]
 pub struct Client {
     api_key: String,
     language: String,
     base: reqwest::Url,
-    client: reqwest::Client,
+    transport: Arc<dyn Transport>,
+    retry_policy: Arc<dyn RetryPolicy>,
+    defaults: CheckOptions,
+    chunk_strategy: ChunkStrategy,
+    fail_on_incomplete: bool,
+    allow_incomplete: bool,
+    #[cfg(feature = "nfc")]
+    normalize_nfc: bool,
+    headers: reqwest::header::HeaderMap,
+    ignored_words: Vec<String>,
+    rule_param_style: RuleParamStyle,
+    strict: bool,
+    limiter: Option<Arc<RateLimiter>>,
+    expand_contractions: bool,
+    ignored_categories: Vec<String>,
+    retry_budget: Option<Arc<AtomicU32>>,
+    lenient_matches: bool,
+    sanitize_control_chars: bool,
+    category_priority: Vec<String>,
+    max_reset_wait: Option<Duration>,
+    fallback_base: Option<reqwest::Url>,
+    timeout: Option<Duration>,
 }
 
 impl Client {
@@ -160,14 +773,707 @@ impl Client {
             api_key: api_key.to_string(),
             language: "en-US".to_string(),
             base: reqwest::Url::parse("http://api.grammarbot.io").unwrap(),
-            client: reqwest::Client::new(),
+            transport: Arc::new(ReqwestTransport::new()),
+            retry_policy: Arc::new(retry::NoRetry),
+            defaults: CheckOptions::default(),
+            chunk_strategy: ChunkStrategy::default(),
+            fail_on_incomplete: false,
+            allow_incomplete: false,
+            #[cfg(feature = "nfc")]
+            normalize_nfc: false,
+            headers: std::iter::once((
+                reqwest::header::USER_AGENT,
+                reqwest::header::HeaderValue::from_static(USER_AGENT),
+            ))
+            .collect(),
+            ignored_words: Vec::new(),
+            rule_param_style: RuleParamStyle::default(),
+            strict: false,
+            limiter: None,
+            expand_contractions: false,
+            ignored_categories: Vec::new(),
+            retry_budget: None,
+            lenient_matches: false,
+            sanitize_control_chars: false,
+            category_priority: Vec::new(),
+            max_reset_wait: None,
+            fallback_base: None,
+            timeout: None,
         }
     }
 
     pub fn check(&self, text: &str) -> Result<Response> {
-        self.request(reqwest::Method::GET, "/v2/check", &[("text", text)])?
-            .json()
-            .context(InvalidJSON)
+        self.check_with(text, &CheckOptions::default())
+    }
+
+    /// Check the text with extra options.
+    ///
+    /// The `options` are layered on top of the client's
+    /// [`defaults`](Self::defaults), see [`CheckOptions`].
+    ///
+    /// ```no_run
+    /// # use grammarbot::{CheckOptions, Client};
+    /// # let client = Client::new("your_api_key");
+    /// let options = CheckOptions {
+    ///     language: Some("en-GB".to_string()),
+    ///     ..CheckOptions::default()
+    /// };
+    /// let response = client.check_with("I like colour.", &options)?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_with(&self, text: &str, options: &CheckOptions) -> Result<Response> {
+        self.check_with_options_meta(text, options)
+            .map(|(response, _)| response)
+    }
+
+    /// Check the text, also returning details about the request.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let (response, meta) = client.check_with_meta("I can't remember how to go their.")?;
+    /// println!("took {} attempts in {:?}", meta.attempts, meta.elapsed);
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_with_meta(&self, text: &str) -> Result<(Response, RequestMeta)> {
+        self.check_with_options_meta(text, &CheckOptions::default())
+    }
+
+    fn check_with_options_meta(
+        &self,
+        text: &str,
+        options: &CheckOptions,
+    ) -> Result<(Response, RequestMeta)> {
+        let options = self.defaults.merge(options);
+        ensure!(!options.runs_nothing(), InvalidRuleConfig);
+        let language = options.language.as_ref().unwrap_or(&self.language);
+        let maps = self.rewrites(text);
+        let sent = maps.last().map_or(text, |map| map.text());
+        let query = self.check_query(sent, &options);
+        let auto = language == "auto";
+        let (response, meta) = self
+            .request_meta(reqwest::Method::GET, "/v2/check", &query)
+            .map_err(|error| language_error(error, language))?;
+        let mut response: Response = if self.lenient_matches {
+            lenient_json(response, self.strict)?
+        } else {
+            json(response, self.strict)?
+        };
+        let detected = &response.language.detected_language.code;
+        ensure!(
+            !auto || !(detected.is_empty() || detected == "zz"),
+            LanguageUndetected
+        );
+        for map in maps.iter().rev() {
+            map.remap(&mut response);
+        }
+        if !self.ignored_words.is_empty() {
+            response.matches.retain(|m| !self.is_ignored_word(m, text));
+        }
+        for m in &mut response.matches {
+            m.dedup_replacements();
+        }
+        response.ignored_categories = self.ignored_categories.clone();
+        response.category_priority = self.category_priority.clone();
+        if response.warnings.incomplete_results && self.fail_on_incomplete && !self.allow_incomplete
+        {
+            return IncompleteResults {
+                response: Box::new(response),
+            }
+            .fail();
+        }
+        Ok((response, meta))
+    }
+
+    /// The query parameters for checking `sent` with merged `options`.
+    fn check_query(&self, sent: &str, options: &CheckOptions) -> Vec<(&'static str, String)> {
+        let language = options.language.as_ref().unwrap_or(&self.language);
+        let mut query = vec![
+            ("api_key", self.api_key.clone()),
+            ("language", language.clone()),
+            ("text", sent.to_string()),
+        ];
+        query.extend(options.query(self.rule_param_style));
+        query
+    }
+
+    /// The request [`check`](Self::check) would send for `text`,
+    /// as a `curl` command for reproducing issues.
+    ///
+    /// The API key is replaced with `REDACTED`, and so are the values of
+    /// credential headers: `Authorization`, `Proxy-Authorization`, `Cookie`,
+    /// and headers from [`default_headers_from_env`](Self::default_headers_from_env).
+    /// See [`as_curl_with`](Self::as_curl_with) to include them.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// println!("{}", client.as_curl("I can't remember how to go their."));
+    /// ```
+    pub fn as_curl(&self, text: &str) -> String {
+        self.as_curl_with(text, false)
+    }
+
+    /// Like [`as_curl`](Self::as_curl), choosing whether to include
+    /// the API key and credential headers.
+    pub fn as_curl_with(&self, text: &str, include_key: bool) -> String {
+        let quote = |value: &str| format!("'{}'", value.replace('\'', "'\\''"));
+        let options = self.defaults.merge(&CheckOptions::default());
+        let maps = self.rewrites(text);
+        let sent = maps.last().map_or(text, |map| map.text());
+        let mut url = self
+            .endpoint("/v2/check")
+            .unwrap_or_else(|_| self.base.clone());
+        url.query_pairs_mut()
+            .extend_pairs(
+                self.check_query(sent, &options)
+                    .into_iter()
+                    .map(|(name, value)| match name {
+                        "api_key" if !include_key => (name, "REDACTED".to_string()),
+                        _ => (name, value),
+                    }),
+            );
+        let mut command = format!("curl -X GET {}", quote(url.as_str()));
+        for (name, value) in &self.headers {
+            let credential = value.is_sensitive()
+                || name == reqwest::header::AUTHORIZATION
+                || name == reqwest::header::PROXY_AUTHORIZATION
+                || name == reqwest::header::COOKIE;
+            let value = if credential && !include_key {
+                "REDACTED".into()
+            } else {
+                String::from_utf8_lossy(value.as_bytes())
+            };
+            command.push_str(&format!(" -H {}", quote(&format!("{}: {}", name, value))));
+        }
+        command
+    }
+
+    /// Whether a match flags one of the ignored words.
+    fn is_ignored_word(&self, m: &types::Match, text: &str) -> bool {
+        m.byte_range(text).is_some_and(|range| {
+            let word = text[range].trim_matches(|c: char| IGNORED_PUNCTUATION.contains(&c));
+            self.ignored_words.iter().any(|ignored| ignored == word)
+        })
+    }
+
+    /// The rewrites to apply to a text before sending it, in order.
+    /// Each one applies to the result of the previous one.
+    fn rewrites(&self, text: &str) -> Vec<offset::TextMap> {
+        let mut steps: Vec<fn(&str) -> offset::TextMap> = Vec::new();
+        #[cfg(feature = "nfc")]
+        {
+            if self.normalize_nfc {
+                steps.push(normalize::nfc);
+            }
+        }
+        if self.sanitize_control_chars {
+            steps.push(sanitize::control_chars);
+        }
+        if self.expand_contractions {
+            steps.push(contractions::expand);
+        }
+        let mut maps: Vec<offset::TextMap> = Vec::new();
+        for step in steps {
+            let map = step(maps.last().map_or(text, offset::TextMap::text));
+            maps.push(map);
+        }
+        maps
+    }
+
+    /// Check the text, dropping matches inside any of the ignored byte ranges.
+    ///
+    /// The whole text is still sent, so the server sees full sentences;
+    /// only matches whose span lies entirely within an ignored range
+    /// are removed.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "Call `frobnicate` twice.";
+    /// let response = client.check_ignoring(text, &[5..17])?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_ignoring(&self, text: &str, ignore_ranges: &[Range<usize>]) -> Result<Response> {
+        let mut response = self.check(text)?;
+        response.matches.retain(|m| match m.byte_range(text) {
+            Some(span) => !ignore_ranges
+                .iter()
+                .any(|ignored| ignored.start <= span.start && span.end <= ignored.end),
+            None => true,
+        });
+        Ok(response)
+    }
+
+    /// Check a long text in chunks of at most `max_chunk` bytes.
+    ///
+    /// Chunks are split between sentences when possible, according to
+    /// the client's [`chunk_strategy`](Self::chunk_strategy).
+    /// Match offsets in the merged response are relative to the whole text.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "A long text. ".repeat(1000);
+    /// let response = client.check_chunked(&text, 2000)?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_chunked(&self, text: &str, max_chunk: usize) -> Result<Response> {
+        self.check_chunked_cancellable(text, max_chunk, &AtomicBool::new(false))
+    }
+
+    /// The number of requests [`check_chunked`](Self::check_chunked) would
+    /// send for `text` with this `max_chunk`, without sending anything.
+    ///
+    /// This follows the client's [`chunk_strategy`](Self::chunk_strategy).
+    /// Retries aren't counted.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "A long text. ".repeat(1000);
+    /// assert_eq!(7, client.estimate_requests(&text, 2000));
+    /// ```
+    pub fn estimate_requests(&self, text: &str, max_chunk: usize) -> usize {
+        chunk::chunks(text, max_chunk, self.chunk_strategy, &SentenceSplitter).len()
+    }
+
+    /// Like [`check_chunked`](Self::check_chunked),
+    /// but stop once `cancel` is set.
+    ///
+    /// The flag is checked before each chunk. If it is set,
+    /// [`Error::Cancelled`] is returned with the matches of the chunks
+    /// checked so far, if any.
+    pub fn check_chunked_cancellable(
+        &self,
+        text: &str,
+        max_chunk: usize,
+        cancel: &AtomicBool,
+    ) -> Result<Response> {
+        self.check_split_cancellable(text, max_chunk, &SentenceSplitter, cancel)
+    }
+
+    /// Like [`check_chunked`](Self::check_chunked), but stop checking
+    /// chunks once `deadline` has passed, returning the matches so far.
+    ///
+    /// The deadline is soft: it is checked between chunks, so a slow
+    /// request is not interrupted, and the first chunk is always checked.
+    /// The response comes with whether the whole text was checked.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # use std::time::Duration;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "A long text. ".repeat(1000);
+    /// let (response, complete) =
+    ///     client.check_chunked_with_deadline(&text, 2000, Duration::from_secs(2))?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_chunked_with_deadline(
+        &self,
+        text: &str,
+        max_chunk: usize,
+        deadline: Duration,
+    ) -> Result<(Response, bool)> {
+        let start = Instant::now();
+        let stop = |checked| checked > 0 && start.elapsed() >= deadline;
+        let (merged, complete) = self.check_split(text, max_chunk, &SentenceSplitter, &stop)?;
+        Ok((merged.expect("the first chunk is always checked"), complete))
+    }
+
+    /// Like [`check_chunked`](Self::check_chunked),
+    /// but splitting sentences with a custom `splitter`.
+    ///
+    /// The default splitter relies on Western punctuation followed by
+    /// whitespace, so Chinese or Japanese text usually ends up as one long
+    /// "sentence" cut at arbitrary characters. [`CjkSplitter`] ends sentences
+    /// at full-width punctuation instead.
+    ///
+    /// `max_chunk` is still in bytes, and most CJK characters take three
+    /// bytes in UTF-8 (but a single UTF-16 code unit in API offsets).
+    /// Match offsets in the merged response are relative to the whole text.
+    ///
+    /// ```no_run
+    /// # use grammarbot::{Client, CjkSplitter};
+    /// # let client = Client::new("your_api_key");
+    /// let text = "今天天气很好。我们去公园吧！";
+    /// let response = client.check_with_custom_sentence_splitter(text, 2000, &CjkSplitter)?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_with_custom_sentence_splitter(
+        &self,
+        text: &str,
+        max_chunk: usize,
+        splitter: &dyn TextSplitter,
+    ) -> Result<Response> {
+        self.check_split_cancellable(text, max_chunk, splitter, &AtomicBool::new(false))
+    }
+
+    /// Check the chunks of `text` in turn, failing with [`Error::Cancelled`]
+    /// once `cancel` is set.
+    fn check_split_cancellable(
+        &self,
+        text: &str,
+        max_chunk: usize,
+        splitter: &dyn TextSplitter,
+        cancel: &AtomicBool,
+    ) -> Result<Response> {
+        let stop = |_| cancel.load(Ordering::SeqCst);
+        match self.check_split(text, max_chunk, splitter, &stop)? {
+            (Some(merged), true) => Ok(merged),
+            (partial, _) => Cancelled {
+                partial: partial.map(Box::new),
+            }
+            .fail(),
+        }
+    }
+
+    /// Check the chunks of `text` in turn, merging their responses.
+    ///
+    /// Before each chunk, `stop` is called with the number of chunks
+    /// checked so far, and checking stops if it returns `true`. Returns
+    /// the merged response, if any chunk was checked, and whether all were.
+    fn check_split(
+        &self,
+        text: &str,
+        max_chunk: usize,
+        splitter: &dyn TextSplitter,
+        stop: &dyn Fn(usize) -> bool,
+    ) -> Result<(Option<Response>, bool)> {
+        let mut merged: Option<Response> = None;
+        let chunks = chunk::chunks(text, max_chunk, self.chunk_strategy, splitter);
+        for (checked, range) in chunks.into_iter().enumerate() {
+            if stop(checked) {
+                return Ok((merged, false));
+            }
+            let mut response = self.check(&text[range.clone()])?;
+            chunk::shift(&mut response, text, range.start);
+            match &mut merged {
+                Some(merged) => chunk::absorb(merged, response),
+                None => merged = Some(response),
+            }
+        }
+        Ok((merged, true))
+    }
+
+    /// Check several short texts in a single request.
+    ///
+    /// The texts are joined with a blank line (`"\n\n"`), which the API
+    /// treats as a paragraph break: it isn't flagged as whitespace or
+    /// punctuation, and sentences don't run across it. Each match is
+    /// attributed to the text it falls in, with offsets relative to that
+    /// text; matches spanning a delimiter are dropped.
+    ///
+    /// This saves requests at the cost of some accuracy, for example when
+    /// a text doesn't end with punctuation. No request is sent for an
+    /// empty list of texts.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let labels = ["Open file", "Save as", "Exti"];
+    /// let matches = client.check_batched_single_request(&labels)?;
+    /// assert_eq!(1, matches[2].len());
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_batched_single_request(&self, texts: &[&str]) -> Result<Vec<Vec<types::Match>>> {
+        const DELIMITER: &str = "\n\n";
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut starts = Vec::with_capacity(texts.len());
+        let mut start = 0;
+        for text in texts {
+            starts.push(start);
+            start += text.encode_utf16().count() + DELIMITER.len();
+        }
+        let response = self.check(&texts.join(DELIMITER))?;
+
+        let mut matches: Vec<Vec<types::Match>> = texts.iter().map(|_| Vec::new()).collect();
+        for mut m in response.matches {
+            let (offset, end) = (m.offset as usize, (m.offset + m.length) as usize);
+            let index = starts
+                .iter()
+                .rposition(|&start| start <= offset)
+                .unwrap_or(0);
+            let text_end = starts[index] + texts[index].encode_utf16().count();
+            if end <= text_end {
+                m.offset -= starts[index] as u32;
+                matches[index].push(m);
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Check each paragraph separately in its own detected language.
+    ///
+    /// Paragraphs are separated by blank lines, and each is checked with
+    /// the `"auto"` language. The merged response has offsets relative to
+    /// the whole text and the language of the first paragraph; the
+    /// [detected language](ParagraphLanguage) of every paragraph
+    /// is returned with it.
+    ///
+    /// A paragraph whose language can't be detected, like a short heading,
+    /// is checked in the client's [`language`](Self::language) instead,
+    /// and marked as a [`fallback`](ParagraphLanguage::fallback).
+    /// If the client's language is `"auto"` too, the check fails with
+    /// [`Error::LanguageUndetected`].
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "I can't remember.\n\nNo me acuerdo.";
+    /// let (response, languages) = client.check_with_language_override_per_paragraph(text)?;
+    /// for paragraph in languages {
+    ///     println!("{:?}: {}", paragraph.range, paragraph.language.code);
+    /// }
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_with_language_override_per_paragraph(
+        &self,
+        text: &str,
+    ) -> Result<(Response, Vec<ParagraphLanguage>)> {
+        let options = CheckOptions {
+            language: Some("auto".to_string()),
+            ..CheckOptions::default()
+        };
+        let mut paragraphs = chunk::paragraphs(text);
+        if paragraphs.is_empty() {
+            paragraphs.push(0..text.len());
+        }
+        let mut merged: Option<Response> = None;
+        let mut languages = Vec::with_capacity(paragraphs.len());
+        for range in paragraphs {
+            let paragraph = &text[range.clone()];
+            let (mut response, fallback) = match self.check_with(paragraph, &options) {
+                Err(Error::LanguageUndetected) if self.language != "auto" => {
+                    (self.check_with(paragraph, &CheckOptions::default())?, true)
+                }
+                result => (result?, false),
+            };
+            let language = if fallback {
+                types::DetectedLanguage {
+                    name: response.language.name.clone(),
+                    code: response.language.code.clone(),
+                    confidence: None,
+                }
+            } else {
+                response.language.detected_language.clone()
+            };
+            languages.push(ParagraphLanguage {
+                range: range.clone(),
+                language,
+                fallback,
+            });
+            chunk::shift(&mut response, text, range.start);
+            match &mut merged {
+                Some(merged) => chunk::absorb(merged, response),
+                None => merged = Some(response),
+            }
+        }
+        Ok((merged.expect("there is always a paragraph"), languages))
+    }
+
+    /// Check each text separately, in order.
+    ///
+    /// A failing text doesn't stop the others from being checked.
+    /// Its error is wrapped in [`Error::InputFailed`] with its index.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// for result in client.check_all(&["Open file", "Save as", "Exti"]) {
+    ///     match result {
+    ///         Ok(response) => println!("{} issues", response.matches.len()),
+    ///         Err(error) => eprintln!("{}", error),
+    ///     }
+    /// }
+    /// ```
+    pub fn check_all(&self, texts: &[&str]) -> Vec<Result<Response>> {
+        texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                self.check(text).map_err(|source| Error::InputFailed {
+                    index,
+                    source: Box::new(source),
+                })
+            })
+            .collect()
+    }
+
+    /// Check each file, pairing each path with its result.
+    ///
+    /// A file that can't be read, isn't valid UTF-8, or fails to be checked
+    /// doesn't stop the others from being checked.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # use std::path::PathBuf;
+    /// # let client = Client::new("your_api_key");
+    /// let paths = vec![PathBuf::from("README.md"), PathBuf::from("CHANGELOG.md")];
+    /// for (path, result) in client.check_paths(&paths) {
+    ///     match result {
+    ///         Ok(response) => println!("{}: {} issues", path.display(), response.matches.len()),
+    ///         Err(error) => eprintln!("{}: {}", path.display(), error),
+    ///     }
+    /// }
+    /// ```
+    pub fn check_paths(&self, paths: &[PathBuf]) -> Vec<(PathBuf, Result<Response>)> {
+        paths
+            .iter()
+            .map(|path| (path.clone(), self.check_path(path)))
+            .collect()
+    }
+
+    fn check_path(&self, path: &Path) -> Result<Response> {
+        let bytes = fs::read(path).context(Io { path })?;
+        let text = String::from_utf8(bytes).context(InvalidUtf8 { path })?;
+        self.check(&text)
+    }
+
+    /// Check the text, re-checking it in candidate languages
+    /// if language detection was unreliable.
+    ///
+    /// This is meant for clients with the `"auto"` language.
+    /// The text is checked once with the configured language; if the
+    /// detected language [is low-confidence](types::DetectedLanguage::is_low_confidence),
+    /// the text is checked again in each of the `candidates`, and the
+    /// response with the fewest matches wins (the first candidate on ties).
+    /// With no candidates, or a confident detection, the first response
+    /// is returned as is.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.language("auto");
+    /// let response = client
+    ///     .check_retrying_language_detection("Je suis", &["fr-FR", "en-US"])?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_retrying_language_detection(
+        &self,
+        text: &str,
+        candidates: &[&str],
+    ) -> Result<Response> {
+        let response = self.check(text)?;
+        if candidates.is_empty() || !response.language.detected_language.is_low_confidence() {
+            return Ok(response);
+        }
+        self.check_multi_language(text, candidates)
+            .map(|(_, response)| response)
+    }
+
+    /// Check the text in each of the `candidates` languages, returning
+    /// the most likely language along with its response.
+    ///
+    /// The language with the fewest matches wins, the first one on ties;
+    /// see [`check_multi_language_with`](Self::check_multi_language_with)
+    /// for other heuristics. With no candidates, the client's language is used.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let (language, response) =
+    ///     client.check_multi_language("Je suis", &["fr-FR", "en-US", "de-DE"])?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_multi_language(
+        &self,
+        text: &str,
+        candidates: &[&str],
+    ) -> Result<(String, Response)> {
+        self.check_multi_language_with(text, candidates, &FewestMatches)
+    }
+
+    /// Like [`check_multi_language`](Self::check_multi_language),
+    /// but choosing the language whose response has the lowest score.
+    ///
+    /// ```no_run
+    /// # use grammarbot::{Client, Severity};
+    /// # let client = Client::new("your_api_key");
+    /// let misspellings = |response: &grammarbot::Response| {
+    ///     let spelling = response.matches.iter().filter(|m| m.severity() == Severity::Spelling);
+    ///     spelling.count() as f64
+    /// };
+    /// let (language, response) =
+    ///     client.check_multi_language_with("Je suis", &["fr-FR", "en-US"], &misspellings)?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn check_multi_language_with(
+        &self,
+        text: &str,
+        candidates: &[&str],
+        scorer: &dyn LanguageScorer,
+    ) -> Result<(String, Response)> {
+        let mut best: Option<(f64, String, Response)> = None;
+        let candidates = match candidates {
+            [] => vec![self.language.as_str()],
+            candidates => candidates.to_vec(),
+        };
+        for language in candidates {
+            let options = CheckOptions {
+                language: Some(language.to_string()),
+                ..CheckOptions::default()
+            };
+            let response = self.check_with(text, &options)?;
+            let score = scorer.score(&response);
+            if best.as_ref().is_none_or(|(best, _, _)| score < *best) {
+                best = Some((score, language.to_string(), response));
+            }
+        }
+        let (_, language, response) = best.expect("there is always a candidate");
+        Ok((language, response))
+    }
+
+    /// List the languages supported by the server.
+    pub fn languages(&self) -> Result<Vec<types::SupportedLanguage>> {
+        json(
+            self.request(reqwest::Method::GET, "/v2/languages", &[])?,
+            self.strict,
+        )
+    }
+
+    /// Validate the client configuration without checking any text.
+    ///
+    /// This checks that an API key is set, the base URL is an HTTP(S) one,
+    /// and the [`defaults`](Self::defaults) enable some rules.
+    /// Unless the language is `"auto"`, the server is also asked for
+    /// its [`languages`](Self::languages) to confirm the configured
+    /// language is supported.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let client = Client::new("your_api_key");
+    /// client.validate_config()?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn validate_config(&self) -> Result<()> {
+        ensure!(!self.api_key.is_empty(), MissingApiKey);
+        ensure!(
+            ["http", "https"].contains(&self.base.scheme()) && self.base.has_host(),
+            InvalidBase {
+                base: self.base.as_str()
+            }
+        );
+        ensure!(!self.defaults.runs_nothing(), InvalidRuleConfig);
+        if self.language != "auto" {
+            let languages = self.languages()?;
+            let supported = languages.iter().any(|language| {
+                language.long_code == self.language || language.code == self.language
+            });
+            ensure!(
+                supported,
+                UnsupportedLanguage {
+                    requested: self.language.as_str(),
+                    supported: languages
+                        .into_iter()
+                        .map(|language| language.long_code)
+                        .collect::<Vec<_>>(),
+                }
+            );
+        }
+        Ok(())
     }
 
     /// Set the API key for the client.
@@ -195,8 +1501,25 @@ impl Client {
         self
     }
 
+    /// A clone of the client checking texts in another language.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let english = Client::new("your_api_key");
+    /// let german = english.clone_with_language("de-DE");
+    /// ```
+    pub fn clone_with_language(&self, language: &str) -> Client {
+        let mut client = self.clone();
+        client.language(language);
+        client
+    }
+
     /// Set the base URL for the client.
     ///
+    /// Endpoints are appended to the base path, with or without
+    /// a trailing slash: both `http://host/api` and `http://host/api/`
+    /// check texts at `http://host/api/v2/check`.
+    ///
     /// ```no_run
     /// # use grammarbot::Client;
     /// # let api_key = "test";
@@ -207,20 +1530,655 @@ impl Client {
         Ok(self)
     }
 
+    /// Set an already parsed base URL for the client.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let url = reqwest::Url::parse("http://pro.grammarbot.io")?;
+    /// let mut client = Client::new("your_api_key");
+    /// client.base_url(url);
+    /// # Ok::<(), reqwest::UrlError>(())
+    /// ```
+    pub fn base_url(&mut self, url: reqwest::Url) -> &mut Self {
+        self.base = url;
+        self
+    }
+
+    /// Set a secondary base URL, used when the base fails.
+    ///
+    /// If a request to the base fails with a server error or without
+    /// a response, after any retries, it is sent to the fallback base
+    /// before failing. [`RequestMeta::base`] tells which one served it.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client
+    ///     .base("http://api.grammarbot.io")?
+    ///     .fallback_base("http://pro.grammarbot.io")?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn fallback_base(&mut self, base: &str) -> Result<&mut Self> {
+        self.fallback_base = Some(reqwest::Url::parse(base).context(InvalidUrl)?);
+        Ok(self)
+    }
+
+    /// Set the default options applied to every check.
+    ///
+    /// Options passed to [`check_with`](Self::check_with) are layered on top.
+    ///
+    /// ```no_run
+    /// # use grammarbot::{CheckOptions, Client};
+    /// let mut client = Client::new("your_api_key");
+    /// client.defaults(CheckOptions {
+    ///     picky: Some(true),
+    ///     disabled_rules: vec!["WHITESPACE_RULE".to_string()],
+    ///     ..CheckOptions::default()
+    /// });
+    /// ```
+    pub fn defaults(&mut self, options: CheckOptions) -> &mut Self {
+        self.defaults = options;
+        self
+    }
+
+    /// Set how [`check_chunked`](Self::check_chunked) splits texts.
+    ///
+    /// ```no_run
+    /// # use grammarbot::{ChunkStrategy, Client};
+    /// let mut client = Client::new("your_api_key");
+    /// client.chunk_strategy(ChunkStrategy::SentenceWithOverlap(1));
+    /// ```
+    pub fn chunk_strategy(&mut self, strategy: ChunkStrategy) -> &mut Self {
+        self.chunk_strategy = strategy;
+        self
+    }
+
+    /// Treat incomplete results as an error.
+    ///
+    /// When the server reports `incompleteResults`, checks fail with
+    /// [`Error::IncompleteResults`] carrying the partial response,
+    /// unless [`allow_incomplete`](Self::allow_incomplete) is also set.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.fail_on_incomplete(true);
+    /// ```
+    pub fn fail_on_incomplete(&mut self, fail: bool) -> &mut Self {
+        self.fail_on_incomplete = fail;
+        self
+    }
+
+    /// Accept incomplete results even if
+    /// [`fail_on_incomplete`](Self::fail_on_incomplete) is set.
+    pub fn allow_incomplete(&mut self, allow: bool) -> &mut Self {
+        self.allow_incomplete = allow;
+        self
+    }
+
+    /// Normalize texts to Unicode NFC before sending them.
+    ///
+    /// Decomposed characters (e.g. `e` followed by a combining acute accent)
+    /// are composed, and match offsets are mapped back to the original text.
+    /// Requires the `nfc` feature.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.normalize_nfc(true);
+    /// ```
+    #[cfg(feature = "nfc")]
+    pub fn normalize_nfc(&mut self, normalize: bool) -> &mut Self {
+        self.normalize_nfc = normalize;
+        self
+    }
+
+    /// Add a header sent with every request.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.header("Authorization", "Bearer gateway-token")?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn header(&mut self, name: &str, value: &str) -> Result<&mut Self> {
+        let (name, value) = parse_header(name, value)?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Identify the tool built on this crate in the `User-Agent` header,
+    /// e.g. `"my-docs-linter/2.0"`.
+    ///
+    /// The header becomes `<suffix> grammarbot-rs/<version>`, keeping
+    /// the crate's own product token. A `User-Agent` set with
+    /// [`header`](Self::header) replaces it entirely.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.user_agent_suffix("my-docs-linter/2.0")?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn user_agent_suffix(&mut self, suffix: &str) -> Result<&mut Self> {
+        self.header("User-Agent", &format!("{} {}", suffix, USER_AGENT))
+    }
+
+    /// Add headers from `GRAMMARBOT_HEADER_<NAME>` environment variables.
+    ///
+    /// The header name is `<NAME>` with underscores replaced by dashes,
+    /// so `GRAMMARBOT_HEADER_X_GATEWAY_TOKEN=secret` sends
+    /// `x-gateway-token: secret`. These headers are considered credentials,
+    /// and redacted by [`as_curl`](Self::as_curl).
+    ///
+    /// Fails with [`InvalidHeader`](Error::InvalidHeader), adding none of
+    /// the headers, if any of these variables is not a valid header,
+    /// including values that are not valid Unicode. Other variables are
+    /// not looked at.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.default_headers_from_env()?;
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn default_headers_from_env(&mut self) -> Result<&mut Self> {
+        const PREFIX: &str = "GRAMMARBOT_HEADER_";
+        let mut headers = Vec::new();
+        for (key, value) in std::env::vars_os() {
+            let key = key.to_string_lossy();
+            let name = match key.strip_prefix(PREFIX) {
+                Some(name) if !name.is_empty() => name.replace('_', "-").to_lowercase(),
+                _ => continue,
+            };
+            let value = value
+                .into_string()
+                .map_err(|_| Error::InvalidHeader { name: name.clone() })?;
+            let (name, mut value) = parse_header(&name, &value)?;
+            value.set_sensitive(true);
+            headers.push((name, value));
+        }
+        for (name, value) in headers {
+            self.headers.insert(name, value);
+        }
+        Ok(self)
+    }
+
+    /// Drop matches flagging any of these words, e.g. project jargon.
+    ///
+    /// Words are compared exactly, after stripping the
+    /// [surrounding punctuation](IGNORED_PUNCTUATION) from the flagged text,
+    /// so that `"Rustacean,"` is ignored along with `"Rustacean"`.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.ignore_words(&["Rustacean", "crates.io"]);
+    /// ```
+    pub fn ignore_words(&mut self, words: &[&str]) -> &mut Self {
+        self.ignored_words = words.iter().map(|word| word.to_string()).collect();
+        self
+    }
+
+    /// Set how rule and category lists are sent in the query.
+    ///
+    /// ```no_run
+    /// # use grammarbot::{Client, RuleParamStyle};
+    /// let mut client = Client::new("your_api_key");
+    /// client.rule_param_style(RuleParamStyle::Repeated);
+    /// ```
+    pub fn rule_param_style(&mut self, style: RuleParamStyle) -> &mut Self {
+        self.rule_param_style = style;
+        self
+    }
+
+    /// Reject responses with fields unknown to this crate.
+    ///
+    /// In strict mode, such responses fail with [`Error::UnknownFields`],
+    /// which helps catch API changes early.
+    pub fn strict(&mut self, strict: bool) -> &mut Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Parse matches one by one, skipping malformed ones.
+    ///
+    /// By default, a single malformed match fails the whole check with
+    /// [`Error::InvalidJSON`]. In lenient mode, the other matches are kept,
+    /// and the skipped ones are counted by [`Response::skipped_matches`].
+    /// The rest of the response must still be well-formed.
+    pub fn lenient_matches(&mut self, lenient: bool) -> &mut Self {
+        self.lenient_matches = lenient;
+        self
+    }
+
+    /// Set a timeout for each request attempt.
+    ///
+    /// The timeout is passed to the [`transport`](Self::transport) with
+    /// each request, so a custom transport is kept, and is responsible
+    /// for honoring it. Clones made before or after keep sharing the
+    /// transport, but only the clones made after have the timeout.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # use std::time::Duration;
+    /// let mut client = Client::new("your_api_key");
+    /// client.timeout(Duration::from_secs(10));
+    /// ```
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Configure the client to be rigorous, e.g. for CI pipelines.
+    ///
+    /// This enables, at once:
+    /// - [`fail_on_incomplete`](Self::fail_on_incomplete), and disables
+    ///   [`allow_incomplete`](Self::allow_incomplete);
+    /// - [`strict`](Self::strict) parsing;
+    /// - a 30 second [`timeout`](Self::timeout), which keeps
+    ///   the [`transport`](Self::transport);
+    /// - up to 3 attempts on transient errors, with
+    ///   [decorrelated jitter](retry::DecorrelatedJitter) between
+    ///   500 milliseconds and 10 seconds.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.strict_profile();
+    /// ```
+    pub fn strict_profile(&mut self) -> &mut Self {
+        self.fail_on_incomplete(true)
+            .allow_incomplete(false)
+            .strict(true)
+            .retry_policy(retry::DecorrelatedJitter::new(
+                Duration::from_millis(500),
+                Duration::from_secs(10),
+                3,
+            ))
+            .timeout(Duration::from_secs(30))
+    }
+
+    /// Rate limit requests, including retries, with a possibly shared limiter.
+    ///
+    /// See [`RateLimiter`] for an example.
+    pub fn shared_limiter(&mut self, limiter: Arc<RateLimiter>) -> &mut Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Set categories, like `"TYPOGRAPHY"`, that are ignored by default.
+    ///
+    /// Matches in these categories are still returned, but
+    /// [`Response::relevant_matches`], [`Response::is_clean`],
+    /// [`Response::is_significant`] and [`Response::to_markdown_report`]
+    /// skip them. Other helpers, and the
+    /// `matches` field itself, see every match. To not check these
+    /// categories at all, disable them in the [`defaults`](Self::defaults).
+    pub fn set_default_ignored_categories(&mut self, categories: &[&str]) -> &mut Self {
+        self.ignored_categories = categories.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Replace control characters with spaces before sending texts.
+    ///
+    /// Replaced are the C0 controls (U+0000 to U+001F) except line feeds
+    /// and tabs, so including carriage returns, DEL (U+007F), and the C1
+    /// controls (U+0080 to U+009F). Each becomes a single space, so that
+    /// words around it stay apart, and match offsets still refer to the
+    /// original text.
+    pub fn sanitize_control_chars(&mut self, sanitize: bool) -> &mut Self {
+        self.sanitize_control_chars = sanitize;
+        self
+    }
+
+    /// Set the order of categories for [`Response::sorted_by_priority`],
+    /// most important first, e.g. `&["GRAMMAR", "TYPOS"]`.
+    pub fn category_priority(&mut self, categories: &[&str]) -> &mut Self {
+        self.category_priority = categories.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Expand contractions like "can't" to "cannot" before sending texts.
+    ///
+    /// Match offsets are mapped back to the original text, so a match
+    /// on "cannot" flags "can't". See [`CONTRACTIONS`](contractions::CONTRACTIONS)
+    /// for what is expanded.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// let mut client = Client::new("your_api_key");
+    /// client.expand_contractions(true);
+    /// ```
+    pub fn expand_contractions(&mut self, expand: bool) -> &mut Self {
+        self.expand_contractions = expand;
+        self
+    }
+
+    /// Set the policy for retrying failed requests.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # use std::time::Duration;
+    /// use grammarbot::retry::Constant;
+    /// let mut client = Client::new("your_api_key");
+    /// client.retry_policy(Constant {
+    ///     delay: Duration::from_secs(1),
+    ///     max_attempts: 3,
+    /// });
+    /// ```
+    pub fn retry_policy(&mut self, policy: impl RetryPolicy + 'static) -> &mut Self {
+        self.retry_policy = Arc::new(policy);
+        self
+    }
+
+    /// Allow at most `total` retries across all requests of this client.
+    ///
+    /// The [`retry_policy`](Self::retry_policy) still decides whether and
+    /// when to retry, but each retry uses up the budget. Once it is spent,
+    /// failed requests fail right away, which protects the quota when the
+    /// server is down for a whole batch.
+    pub fn retry_budget(&mut self, total: u32) -> &mut Self {
+        self.retry_budget = Some(Arc::new(AtomicU32::new(total)));
+        self
+    }
+
+    /// Wait until the rate limit resets before retrying `429 Too Many Requests`.
+    ///
+    /// When the [`retry_policy`](Self::retry_policy) retries a response
+    /// with an `X-RateLimit-Reset` header, the client waits until that time
+    /// instead of the policy's delay, as long as it is at most `max_wait` away.
+    /// Later resets fail right away with [`QuotaExceeded`](Error::QuotaExceeded).
+    pub fn wait_for_rate_limit_reset(&mut self, max_wait: Duration) -> &mut Self {
+        self.max_reset_wait = Some(max_wait);
+        self
+    }
+
+    /// Take one retry from the budget, if there is one left.
+    fn spend_retry(&self) -> bool {
+        self.retry_budget.as_ref().is_none_or(|budget| {
+            budget
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                    left.checked_sub(1)
+                })
+                .is_ok()
+        })
+    }
+
+    /// Set the transport sending HTTP requests.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// use grammarbot::transport::ReqwestTransport;
+    /// let mut client = Client::new("your_api_key");
+    /// client.transport(ReqwestTransport::new());
+    /// ```
+    pub fn transport(&mut self, transport: impl Transport + 'static) -> &mut Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// The URL of an API endpoint, under the path of the base URL.
+    fn endpoint(&self, path: &str) -> Result<reqwest::Url> {
+        endpoint(&self.base, path)
+    }
+
     fn request(
         &self,
         method: reqwest::Method,
         path: &str,
-        query: &[(&str, &str)],
-    ) -> Result<reqwest::Response> {
-        self.client
-            .request(method, self.base.join(path).context(InvalidUrl)?)
-            .query(&query)
-            .send()
-            .context(RequestFailed)
+        query: &[(&str, String)],
+    ) -> Result<RawResponse> {
+        self.request_meta(method, path, query)
+            .map(|(response, _)| response)
+    }
+
+    fn request_meta(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<(RawResponse, RequestMeta)> {
+        let start = Instant::now();
+        let mut attempt = 0;
+        let result = self.send_to(&self.base, &method, path, query, start, &mut attempt);
+        match (&self.fallback_base, result) {
+            (Some(fallback), Err(error)) if should_fall_back(&error) => {
+                self.send_to(fallback, &method, path, query, start, &mut attempt)
+            }
+            (_, result) => result,
+        }
+    }
+
+    /// Send a request to an endpoint under `base`, retrying as configured.
+    ///
+    /// `attempt` counts the attempts across calls, for the request meta.
+    fn send_to(
+        &self,
+        base: &reqwest::Url,
+        method: &reqwest::Method,
+        path: &str,
+        query: &[(&str, String)],
+        start: Instant,
+        attempt: &mut u32,
+    ) -> Result<(RawResponse, RequestMeta)> {
+        let url = endpoint(base, path)?;
+        let first = *attempt;
+        loop {
+            *attempt += 1;
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire();
+            }
+            let result = self.transport.send(
+                method.clone(),
+                &url,
+                query,
+                &self.headers,
+                None,
+                self.timeout,
+            );
+            let mut reset_at = None;
+            let status = match &result {
+                Ok(response) if response.status.is_success() => {
+                    let meta = RequestMeta {
+                        attempts: *attempt,
+                        elapsed: start.elapsed(),
+                        status: response.status,
+                        base: base.clone(),
+                    };
+                    return result.map(|response| (response, meta));
+                }
+                Ok(response) => {
+                    if response.status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                        reset_at = rate_limit_reset(&response.headers);
+                    }
+                    Some(response.status)
+                }
+                Err(_) => None,
+            };
+            let delay = self
+                .retry_policy
+                .next_delay(*attempt - first, status)
+                .and_then(|delay| match reset_at.zip(self.max_reset_wait) {
+                    Some((reset_at, max_wait)) => {
+                        let wait = reset_at
+                            .duration_since(SystemTime::now())
+                            .unwrap_or_default();
+                        Some(wait).filter(|&wait| wait <= max_wait)
+                    }
+                    None => Some(delay),
+                });
+            if let Some(delay) = delay {
+                if self.spend_retry() {
+                    thread::sleep(delay);
+                    continue;
+                }
+            }
+            let response = result?;
+            if response.status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return QuotaExceeded {
+                    reset_at,
+                    body: response.body,
+                }
+                .fail();
+            }
+            return Api {
+                status: response.status,
+                body: response.body,
+            }
+            .fail();
+        }
+    }
+}
+
+/// Parse a header name and value.
+fn parse_header(
+    name: &str,
+    value: &str,
+) -> Result<(reqwest::header::HeaderName, reqwest::header::HeaderValue)> {
+    let invalid = || Error::InvalidHeader {
+        name: name.to_string(),
+    };
+    let header_name =
+        reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+    let header_value = reqwest::header::HeaderValue::from_str(value).map_err(|_| invalid())?;
+    Ok((header_name, header_value))
+}
+
+/// The URL of an API endpoint, under the path of `base`.
+///
+/// `Url::join` replaces the last path segment of a base without a
+/// trailing slash, and a path with a leading slash replaces the whole
+/// base path, so both are normalized first.
+fn endpoint(base: &reqwest::Url, path: &str) -> Result<reqwest::Url> {
+    let mut base = base.clone();
+    if !base.path().ends_with('/') {
+        let directory = format!("{}/", base.path());
+        base.set_path(&directory);
+    }
+    base.join(path.trim_start_matches('/')).context(InvalidUrl)
+}
+
+/// Whether a request failing with `error` should be sent to the fallback
+/// base: it failed without a response, or with a server error.
+fn should_fall_back(error: &Error) -> bool {
+    match error {
+        Error::RequestFailed { .. } | Error::DnsFailure { .. } => true,
+        Error::Api { status, .. } => status.is_server_error(),
+        _ => false,
     }
 }
 
+/// The time the rate limit resets, from the `X-RateLimit-Reset` header
+/// as a unix timestamp in seconds.
+fn rate_limit_reset(headers: &reqwest::header::HeaderMap) -> Option<SystemTime> {
+    let seconds = headers.get("x-ratelimit-reset")?.to_str().ok()?;
+    let seconds: u64 = seconds.trim().parse().ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(seconds))
+}
+
+/// Turn client errors about the language into specific errors.
+fn language_error(error: Error, language: &str) -> Error {
+    let body = match &error {
+        Error::Api { status, body } if status.is_client_error() => body,
+        _ => return error,
+    };
+    const SUPPORTED: &str = "supported languages:";
+    let lowercase = body.to_ascii_lowercase();
+    if language == "auto" && lowercase.contains("detect") {
+        return Error::LanguageUndetected;
+    }
+    match lowercase.find(SUPPORTED) {
+        Some(start) => {
+            let list = body[start + SUPPORTED.len()..].lines().next();
+            let supported = list
+                .unwrap_or_default()
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .map(|code| code.trim_matches(|c: char| !c.is_alphanumeric() && c != '-'))
+                .filter(|code| !code.is_empty())
+                .map(str::to_string)
+                .collect();
+            Error::UnsupportedLanguage {
+                requested: language.to_string(),
+                supported,
+            }
+        }
+        None => error,
+    }
+}
+
+/// Parse a JSON response body.
+///
+/// The transport has already decompressed the body at this point, so the
+/// content type is that of the actual payload, even for compressed
+/// error pages served with a successful status.
+///
+/// In `strict` mode, fields unknown to this crate are an error.
+fn json<T: DeserializeOwned>(response: RawResponse, strict: bool) -> Result<T> {
+    if let Some(content_type) = response.headers.get(reqwest::header::CONTENT_TYPE) {
+        let content_type = content_type.to_str().unwrap_or_default();
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        ensure!(
+            essence == "application/json" || essence.ends_with("+json"),
+            UnexpectedContentType { content_type }
+        );
+    }
+    let mut deserializer = serde_json::Deserializer::from_str(&response.body);
+    let mut unknown = Vec::new();
+    let value: T = if strict {
+        serde_ignored::deserialize(&mut deserializer, |path| unknown.push(path.to_string()))
+    } else {
+        T::deserialize(&mut deserializer)
+    }
+    .context(InvalidJSON)?;
+    ensure!(unknown.is_empty(), UnknownFields { fields: unknown });
+    Ok(value)
+}
+
+/// Parse a check response like [`json`], but skipping malformed matches.
+fn lenient_json(response: RawResponse, strict: bool) -> Result<Response> {
+    let mut value: serde_json::Value = json(response, false)?;
+    let matches = match value.get_mut("matches") {
+        Some(matches) if matches.is_array() => matches.take(),
+        _ => serde_json::Value::Null,
+    };
+    if matches.is_array() {
+        value["matches"] = serde_json::Value::Array(Vec::new());
+    }
+    let mut unknown = Vec::new();
+    let mut response: Response = from_value(value, strict, "", &mut unknown)?;
+    if let serde_json::Value::Array(matches) = matches {
+        for (index, m) in matches.into_iter().enumerate() {
+            let mut unknown_in_match = Vec::new();
+            let prefix = format!("matches.{}.", index);
+            match from_value(m, strict, &prefix, &mut unknown_in_match) {
+                Ok(m) => {
+                    response.matches.push(m);
+                    unknown.extend(unknown_in_match);
+                }
+                Err(_) => response.skipped_matches += 1,
+            }
+        }
+    }
+    ensure!(unknown.is_empty(), UnknownFields { fields: unknown });
+    Ok(response)
+}
+
+/// Deserialize a JSON value, recording unknown fields under `prefix` if `strict`.
+fn from_value<T: DeserializeOwned>(
+    value: serde_json::Value,
+    strict: bool,
+    prefix: &str,
+    unknown: &mut Vec<String>,
+) -> Result<T> {
+    if strict {
+        serde_ignored::deserialize(value, |path| unknown.push(format!("{}{}", prefix, path)))
+    } else {
+        T::deserialize(value)
+    }
+    .context(InvalidJSON)
+}
+
 /// A domain-specific error type.
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -230,17 +2188,132 @@ pub enum Error {
         /// A source error from `reqwest`.
         source: reqwest::Error,
     },
+    /// A request failed because the API host name could not be resolved,
+    /// e.g. because of a typo in the [`base`](Client::base) URL.
+    #[snafu(display("could not resolve host {}: {}", host, source))]
+    DnsFailure {
+        /// The host name.
+        host: String,
+        /// A source error from `std`.
+        source: std::io::Error,
+    },
     /// An invalid URL was supplied to [`parse`](reqwest::Url::parse).
     #[snafu(display("invalid URL: {}", source))]
     InvalidUrl {
         /// A source error from `reqwest`.
         source: reqwest::UrlError,
     },
+    /// The API responded with an unsuccessful status.
+    #[snafu(display("API error: {}", status))]
+    Api {
+        /// The response status.
+        status: reqwest::StatusCode,
+        /// The response body, which usually describes the error.
+        body: String,
+    },
+    /// The API responded with `429 Too Many Requests`.
+    #[snafu(display("quota exceeded"))]
+    QuotaExceeded {
+        /// When the rate limit resets, if the server said so
+        /// with an `X-RateLimit-Reset` header.
+        reset_at: Option<SystemTime>,
+        /// The response body, which usually describes the error.
+        body: String,
+    },
+    /// A file could not be read.
+    #[snafu(display("could not read {}: {}", path.display(), source))]
+    Io {
+        /// The file path.
+        path: PathBuf,
+        /// A source error from `std`.
+        source: std::io::Error,
+    },
+    /// A file is not valid UTF-8.
+    #[snafu(display("{} is not valid UTF-8: {}", path.display(), source))]
+    InvalidUtf8 {
+        /// The file path.
+        path: PathBuf,
+        /// A source error from `std`.
+        source: std::string::FromUtf8Error,
+    },
+    /// An unknown encoding label was supplied to `check_encoded`.
+    #[snafu(display("unknown encoding: {}", encoding))]
+    UnknownEncoding {
+        /// The encoding label.
+        encoding: String,
+    },
+    /// An invalid header name or value was supplied.
+    #[snafu(display("invalid header: {}", name))]
+    InvalidHeader {
+        /// The header name.
+        name: String,
+    },
+    /// No API key was set.
+    #[snafu(display("missing API key"))]
+    MissingApiKey,
+    /// The base URL can't be used to reach the API.
+    #[snafu(display("invalid base URL: {}", base))]
+    InvalidBase {
+        /// The configured base URL.
+        base: String,
+    },
+    /// The server couldn't detect the language of a text checked with
+    /// the `"auto"` language, usually because the text is too short.
+    /// Specifying the language avoids this.
+    #[snafu(display("could not detect the language"))]
+    LanguageUndetected,
+    /// The requested language is not supported by the server: it rejected
+    /// a check in that language, or [`validate_config`](Client::validate_config)
+    /// didn't find it among the server's [`languages`](Client::languages).
+    #[snafu(display("unsupported language: {}", requested))]
+    UnsupportedLanguage {
+        /// The requested language code.
+        requested: String,
+        /// The supported language codes, as listed by the server.
+        /// From `validate_config`, these are the long codes, like `"en-US"`.
+        supported: Vec<String>,
+    },
+    /// Only enabled rules are to be run, but none are enabled, see
+    /// [`enabled_only`](CheckOptions::enabled_only).
+    #[snafu(display("enabled_only is set without any enabled rules or categories"))]
+    InvalidRuleConfig,
+    /// The operation was cancelled by the caller.
+    #[snafu(display("cancelled"))]
+    Cancelled {
+        /// The response collected before cancellation, if any.
+        partial: Option<Box<Response>>,
+    },
+    /// The server only checked part of the text.
+    #[snafu(display("incomplete results"))]
+    IncompleteResults {
+        /// The partial response.
+        response: Box<Response>,
+    },
+    /// One of the texts of a batch failed to be checked.
+    #[snafu(display("input #{} failed: {}", index, source))]
+    InputFailed {
+        /// The index of the text in the batch.
+        index: usize,
+        /// The error for that text.
+        source: Box<Error>,
+    },
+    /// Response is not JSON, e.g. an HTML error page.
+    #[snafu(display("unexpected content type: {}", content_type))]
+    UnexpectedContentType {
+        /// The `Content-Type` of the response.
+        content_type: String,
+    },
     /// Response returned invalid JSON.
     #[snafu(display("invalid JSON: {}", source))]
     InvalidJSON {
-        /// A source error from `reqwest`.
-        source: reqwest::Error,
+        /// A source error from `serde_json`.
+        source: serde_json::Error,
+    },
+    /// Response has fields unknown to this crate, in strict mode.
+    #[snafu(display("unknown fields: {}", fields.join(", ")))]
+    UnknownFields {
+        /// The paths of the unknown fields, e.g. `matches.0.extra`.
+        fields: Vec<String>,
     },
 }
 
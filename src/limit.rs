@@ -0,0 +1,51 @@
+//! Rate limiting of requests, possibly shared between clients.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Spaces out requests so that at most `requests` are sent `per` interval.
+///
+/// A limiter can be shared between clients with
+/// [`shared_limiter`](crate::Client::shared_limiter),
+/// bounding their combined request rate.
+///
+/// ```no_run
+/// # use grammarbot::{Client, RateLimiter};
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// let limiter = Arc::new(RateLimiter::new(10, Duration::from_secs(1)));
+/// let mut english = Client::new("your_api_key");
+/// english.shared_limiter(limiter.clone());
+/// let mut german = Client::new("your_api_key");
+/// german.language("de-DE").shared_limiter(limiter);
+/// ```
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Allow `requests` requests `per` interval, evenly spaced.
+    pub fn new(requests: u32, per: Duration) -> Self {
+        Self {
+            interval: per / requests.max(1),
+            next: Mutex::new(None),
+        }
+    }
+
+    /// Block until a request may be sent.
+    pub fn acquire(&self) {
+        let mut next = self.next.lock().unwrap();
+        let now = Instant::now();
+        let at = match *next {
+            Some(at) if at > now => {
+                thread::sleep(at - now);
+                at
+            }
+            _ => now,
+        };
+        *next = Some(at + self.interval);
+    }
+}
@@ -0,0 +1,34 @@
+//! Unicode normalization of texts before checking.
+
+use crate::offset::TextMap;
+use unicode_normalization::char::canonical_combining_class;
+use unicode_normalization::UnicodeNormalization;
+
+/// Normalize `text` to NFC.
+///
+/// Each character is composed with the combining marks following it,
+/// so offsets in the normalized text map back to whole characters
+/// of the original.
+pub(crate) fn nfc(text: &str) -> TextMap {
+    TextMap::rewrite(clusters(text), |cluster| cluster.nfc().collect())
+}
+
+/// Split `text` into characters with their combining marks.
+fn clusters(text: &str) -> impl Iterator<Item = &str> {
+    let mut starts = text
+        .char_indices()
+        .filter(|&(_, c)| canonical_combining_class(c) == 0)
+        .map(|(index, _)| index)
+        .skip_while(|&index| index == 0)
+        .peekable();
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        if start >= text.len() {
+            return None;
+        }
+        let end = starts.next().unwrap_or(text.len());
+        let cluster = &text[start..end];
+        start = end;
+        Some(cluster)
+    })
+}
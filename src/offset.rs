@@ -0,0 +1,250 @@
+//! Conversions between the API's offsets and byte offsets.
+//!
+//! The API counts offsets and lengths in UTF-16 code units,
+//! while Rust strings are indexed by bytes.
+
+/// Convert a UTF-16 offset into `text` to a byte offset.
+///
+/// Returns `None` if the offset is past the end of `text`
+/// or doesn't fall on a character boundary.
+///
+/// ```
+/// use grammarbot::offset::utf16_to_byte;
+/// assert_eq!(Some(3), utf16_to_byte("né!", 2));
+/// ```
+pub fn utf16_to_byte(text: &str, offset: usize) -> Option<usize> {
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        if units == offset {
+            return Some(index);
+        }
+        if units > offset {
+            return None;
+        }
+        units += c.len_utf16();
+    }
+    if units == offset {
+        Some(text.len())
+    } else {
+        None
+    }
+}
+
+/// Like [`utf16_to_byte`], but an offset inside a character (between the
+/// two code units of a surrogate pair, e.g. in an emoji) is rounded down
+/// to the start of the character.
+///
+/// Returns `None` if the offset is past the end of `text`.
+///
+/// ```
+/// use grammarbot::offset::utf16_to_byte_floor;
+/// assert_eq!(Some(1), utf16_to_byte_floor("a😀b", 2));
+/// ```
+pub fn utf16_to_byte_floor(text: &str, offset: usize) -> Option<usize> {
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        units += c.len_utf16();
+        if offset < units {
+            return Some(index);
+        }
+    }
+    if offset == units {
+        Some(text.len())
+    } else {
+        None
+    }
+}
+
+/// Like [`utf16_to_byte`], but an offset inside a character (between the
+/// two code units of a surrogate pair, e.g. in an emoji) is rounded up
+/// to the end of the character.
+///
+/// Returns `None` if the offset is past the end of `text`.
+///
+/// ```
+/// use grammarbot::offset::utf16_to_byte_ceil;
+/// assert_eq!(Some(5), utf16_to_byte_ceil("a😀b", 2));
+/// ```
+pub fn utf16_to_byte_ceil(text: &str, offset: usize) -> Option<usize> {
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        if offset <= units {
+            return Some(index);
+        }
+        units += c.len_utf16();
+    }
+    if offset <= units {
+        Some(text.len())
+    } else {
+        None
+    }
+}
+
+/// Convert a byte offset into `text` to a UTF-16 offset.
+///
+/// Offsets inside a character or past the end are rounded down
+/// to the preceding character boundary.
+///
+/// ```
+/// use grammarbot::offset::byte_to_utf16;
+/// assert_eq!(2, byte_to_utf16("né!", 3));
+/// ```
+pub fn byte_to_utf16(text: &str, offset: usize) -> usize {
+    text.char_indices()
+        .take_while(|&(index, c)| index + c.len_utf8() <= offset)
+        .map(|(_, c)| c.len_utf16())
+        .sum()
+}
+
+/// Line starts of a text, for converting byte offsets to lines and columns.
+///
+/// ```
+/// use grammarbot::offset::LineIndex;
+/// let index = LineIndex::new("one\ntwo");
+/// assert_eq!((2, 2), index.line_col(5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Index the lines of `text`.
+    pub fn new(text: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { text, starts }
+    }
+
+    /// The 1-based line and column of a byte offset.
+    ///
+    /// Columns are counted in characters.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let line = match self.starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        let start = self.starts[line];
+        let column = self.text[start..offset.min(self.text.len())]
+            .chars()
+            .count();
+        (line + 1, column + 1)
+    }
+}
+
+/// Where a match is in the checked text, in all the units renderers need,
+/// as returned by [`Match::location`](crate::types::Match::location).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The 1-based line of the start of the match.
+    pub line: u32,
+    /// The 1-based column of the start of the match, in characters.
+    pub column: u32,
+    /// The byte range of the match.
+    pub byte_range: std::ops::Range<usize>,
+    /// The UTF-16 range of the match, as reported by the API.
+    pub utf16_range: std::ops::Range<usize>,
+}
+
+/// A rewritten text, mapping offsets in it back to the original.
+///
+/// Built from segments of the original text, each either kept as is
+/// or replaced. Offsets inside a replaced segment map to its edges.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TextMap {
+    text: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    /// UTF-16 range in the original text.
+    original: std::ops::Range<usize>,
+    /// UTF-16 range in the rewritten text.
+    rewritten: std::ops::Range<usize>,
+    kept: bool,
+}
+
+impl TextMap {
+    /// Rewrite `text` piece by piece, replacing each piece with the result
+    /// of `rewrite`. Changed pieces become replaced segments.
+    pub(crate) fn rewrite<'a, I, F>(pieces: I, mut rewrite: F) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+        F: FnMut(&str) -> String,
+    {
+        let mut map = Self::default();
+        for piece in pieces {
+            let rewritten = rewrite(piece);
+            let kept = rewritten == piece;
+            map.push(piece, &rewritten, kept);
+        }
+        map
+    }
+
+    fn push(&mut self, original: &str, rewritten: &str, kept: bool) {
+        let (original_start, rewritten_start) = self
+            .segments
+            .last()
+            .map_or((0, 0), |last| (last.original.end, last.rewritten.end));
+        let original_len: usize = original.chars().map(char::len_utf16).sum();
+        let rewritten_len: usize = rewritten.chars().map(char::len_utf16).sum();
+        match self.segments.last_mut() {
+            Some(last) if kept && last.kept => {
+                last.original.end += original_len;
+                last.rewritten.end += rewritten_len;
+            }
+            _ => self.segments.push(Segment {
+                original: original_start..original_start + original_len,
+                rewritten: rewritten_start..rewritten_start + rewritten_len,
+                kept,
+            }),
+        }
+        self.text.push_str(rewritten);
+    }
+
+    /// The rewritten text.
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Map a UTF-16 span in the rewritten text to the original text.
+    pub(crate) fn map_span(&self, offset: usize, length: usize) -> (usize, usize) {
+        let start = self.map(offset, false);
+        let end = self.map(offset + length, true).max(start);
+        (start, end - start)
+    }
+
+    fn map(&self, offset: usize, is_end: bool) -> usize {
+        let segment = self.segments.iter().find(|segment| {
+            if is_end {
+                segment.rewritten.start < offset && offset <= segment.rewritten.end
+            } else {
+                segment.rewritten.start <= offset && offset < segment.rewritten.end
+            }
+        });
+        match segment {
+            Some(segment) if segment.kept => {
+                segment.original.start + (offset - segment.rewritten.start)
+            }
+            Some(segment) if is_end => segment.original.end,
+            Some(segment) => segment.original.start,
+            None => self
+                .segments
+                .last()
+                .map_or(offset, |last| last.original.end),
+        }
+    }
+
+    /// Map the matches of a response for the rewritten text
+    /// back to the original text.
+    pub(crate) fn remap(&self, response: &mut crate::Response) {
+        for m in &mut response.matches {
+            let (offset, length) = self.map_span(m.offset as usize, m.length as usize);
+            m.offset = offset as u32;
+            m.length = length as u32;
+        }
+    }
+}
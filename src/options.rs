@@ -0,0 +1,136 @@
+//! Per-request options for [`check_with`](crate::Client::check_with).
+
+/// Options tweaking how a text is checked.
+///
+/// Options are layered: options passed to a request take precedence over the
+/// client's [`defaults`](crate::Client::defaults), which take precedence over
+/// the client's own configuration (e.g. its [`language`](crate::Client::language)).
+///
+/// ```
+/// use grammarbot::CheckOptions;
+/// let options = CheckOptions {
+///     picky: Some(true),
+///     disabled_rules: vec!["WHITESPACE_RULE".to_string()],
+///     ..CheckOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckOptions {
+    /// The language to check in, e.g. `"en-US"` or `"auto"`.
+    pub language: Option<String>,
+    /// Rule IDs to enable.
+    pub enabled_rules: Vec<String>,
+    /// Rule IDs to disable.
+    pub disabled_rules: Vec<String>,
+    /// Category IDs to enable.
+    pub enabled_categories: Vec<String>,
+    /// Category IDs to disable.
+    pub disabled_categories: Vec<String>,
+    /// Run only the enabled rules and categories.
+    ///
+    /// Checks fail with [`InvalidRuleConfig`](crate::Error::InvalidRuleConfig)
+    /// if this is set without any enabled rules or categories,
+    /// since the server would run no rules at all.
+    pub enabled_only: Option<bool>,
+    /// Run additional rules that are usually too noisy.
+    pub picky: Option<bool>,
+}
+
+impl CheckOptions {
+    /// Layer `overrides` on top of these options.
+    ///
+    /// Optional values from `overrides` win when set. Lists are combined,
+    /// and a rule or category enabled by one layer is no longer disabled
+    /// by the other, and vice versa.
+    pub fn merge(&self, overrides: &CheckOptions) -> CheckOptions {
+        CheckOptions {
+            language: overrides.language.clone().or_else(|| self.language.clone()),
+            enabled_rules: layer(
+                &self.enabled_rules,
+                &overrides.enabled_rules,
+                &overrides.disabled_rules,
+            ),
+            disabled_rules: layer(
+                &self.disabled_rules,
+                &overrides.disabled_rules,
+                &overrides.enabled_rules,
+            ),
+            enabled_categories: layer(
+                &self.enabled_categories,
+                &overrides.enabled_categories,
+                &overrides.disabled_categories,
+            ),
+            disabled_categories: layer(
+                &self.disabled_categories,
+                &overrides.disabled_categories,
+                &overrides.enabled_categories,
+            ),
+            enabled_only: overrides.enabled_only.or(self.enabled_only),
+            picky: overrides.picky.or(self.picky),
+        }
+    }
+
+    /// Whether these options would have the server run no rules at all.
+    pub(crate) fn runs_nothing(&self) -> bool {
+        self.enabled_only == Some(true)
+            && self.enabled_rules.is_empty()
+            && self.enabled_categories.is_empty()
+    }
+
+    /// The query parameters for these options, except the language.
+    pub(crate) fn query(&self, style: RuleParamStyle) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        let lists = [
+            ("enabledRules", &self.enabled_rules),
+            ("disabledRules", &self.disabled_rules),
+            ("enabledCategories", &self.enabled_categories),
+            ("disabledCategories", &self.disabled_categories),
+        ];
+        for (name, values) in lists.iter() {
+            match style {
+                RuleParamStyle::CommaJoined if !values.is_empty() => {
+                    query.push((*name, values.join(",")))
+                }
+                RuleParamStyle::CommaJoined => {}
+                RuleParamStyle::Repeated => {
+                    query.extend(values.iter().map(|value| (*name, value.clone())))
+                }
+            }
+        }
+        if let Some(enabled_only) = self.enabled_only {
+            query.push(("enabledOnly", enabled_only.to_string()));
+        }
+        if let Some(picky) = self.picky {
+            let level = if picky { "picky" } else { "default" };
+            query.push(("level", level.to_string()));
+        }
+        query
+    }
+}
+
+/// How rule and category lists are sent, see
+/// [`rule_param_style`](crate::Client::rule_param_style).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RuleParamStyle {
+    /// A single comma-joined parameter, `enabledRules=A,B`.
+    /// This is what GrammarBot expects, and the default.
+    #[default]
+    CommaJoined,
+    /// A repeated parameter, `enabledRules=A&enabledRules=B`,
+    /// as expected by some LanguageTool-compatible servers.
+    Repeated,
+}
+
+fn layer(base: &[String], added: &[String], removed: &[String]) -> Vec<String> {
+    let mut values: Vec<String> = base
+        .iter()
+        .filter(|value| !removed.contains(value))
+        .cloned()
+        .collect();
+    for value in added {
+        if !values.contains(value) {
+            values.push(value.clone());
+        }
+    }
+    values
+}
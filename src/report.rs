@@ -0,0 +1,59 @@
+//! Human-readable reports of responses.
+
+use crate::offset::LineIndex;
+use crate::Response;
+
+impl Response {
+    /// Render the matches as a Markdown table, under a summary header.
+    ///
+    /// Each match gets a row with its `line:column` location in `text`,
+    /// its rule, its message and its top suggestion. Matches in the client's
+    /// [default ignored categories](crate::Client::set_default_ignored_categories)
+    /// are left out.
+    ///
+    /// ```no_run
+    /// # use grammarbot::Client;
+    /// # let client = Client::new("your_api_key");
+    /// let text = "I can't remember how to go their.";
+    /// let response = client.check(text)?;
+    /// println!("{}", response.to_markdown_report(text));
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn to_markdown_report(&self, text: &str) -> String {
+        let matches: Vec<_> = self.relevant_matches().collect();
+        let mut report = match matches.len() {
+            0 => return "## GrammarBot: no issues found\n".to_string(),
+            1 => "## GrammarBot: 1 issue found\n\n".to_string(),
+            n => format!("## GrammarBot: {} issues found\n\n", n),
+        };
+        report.push_str("| Location | Rule | Message | Suggestion |\n");
+        report.push_str("| --- | --- | --- | --- |\n");
+        let lines = LineIndex::new(text);
+        for m in matches {
+            let location = match m.byte_range(text) {
+                Some(range) => {
+                    let (line, column) = lines.line_col(range.start);
+                    format!("{}:{}", line, column)
+                }
+                None => "?".to_string(),
+            };
+            let suggestion = m
+                .replacements
+                .first()
+                .map_or_else(String::new, |r| format!("`{}`", escape(&r.value)));
+            report.push_str(&format!(
+                "| {} | `{}` | {} | {} |\n",
+                location,
+                m.rule.id,
+                escape(&m.message),
+                suggestion
+            ));
+        }
+        report
+    }
+}
+
+/// Escape text for a Markdown table cell.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
@@ -0,0 +1,119 @@
+//! Policies deciding whether and when to retry failed requests.
+//!
+//! A [`RetryPolicy`] can be set with [`retry_policy`](crate::Client::retry_policy).
+//! By default, requests are never retried.
+
+use reqwest::StatusCode;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Decides how long to wait before retrying a failed request.
+pub trait RetryPolicy: Send + Sync {
+    /// The delay before the next attempt, or `None` to stop retrying.
+    ///
+    /// `attempt` is the number of attempts made so far, starting at 1.
+    /// `status` is the status of the failed response, or `None`
+    /// if the request failed without one (e.g. a connection error).
+    fn next_delay(&self, attempt: u32, status: Option<StatusCode>) -> Option<Duration>;
+}
+
+/// Whether a failure is likely to go away on retry:
+/// a connection error, `429 Too Many Requests`, or a server error.
+pub fn is_transient(status: Option<StatusCode>) -> bool {
+    match status {
+        None => true,
+        Some(status) => status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+    }
+}
+
+/// Never retry. This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn next_delay(&self, _attempt: u32, _status: Option<StatusCode>) -> Option<Duration> {
+        None
+    }
+}
+
+/// Retry transient failures after the same delay every time.
+#[derive(Debug, Clone, Copy)]
+pub struct Constant {
+    /// The delay between attempts.
+    pub delay: Duration,
+    /// The total number of attempts, including the first one.
+    pub max_attempts: u32,
+}
+
+impl RetryPolicy for Constant {
+    fn next_delay(&self, attempt: u32, status: Option<StatusCode>) -> Option<Duration> {
+        if attempt >= self.max_attempts || !is_transient(status) {
+            return None;
+        }
+        Some(self.delay)
+    }
+}
+
+/// Retry transient failures, waiting `step` longer after each attempt.
+#[derive(Debug, Clone, Copy)]
+pub struct Linear {
+    /// The delay added with each attempt.
+    pub step: Duration,
+    /// The total number of attempts, including the first one.
+    pub max_attempts: u32,
+}
+
+impl RetryPolicy for Linear {
+    fn next_delay(&self, attempt: u32, status: Option<StatusCode>) -> Option<Duration> {
+        if attempt >= self.max_attempts || !is_transient(status) {
+            return None;
+        }
+        Some(self.step * attempt)
+    }
+}
+
+/// Retry transient failures with "decorrelated jitter":
+/// each delay is random between `base` and `base * 3^attempt`,
+/// the most that three times the previous delay can grow to,
+/// capped at `cap`.
+///
+/// The delay only depends on the attempt number, so the policy can be
+/// shared by concurrent requests, and by clones of a client.
+#[derive(Debug, Clone, Copy)]
+pub struct DecorrelatedJitter {
+    base: Duration,
+    cap: Duration,
+    max_attempts: u32,
+}
+
+impl DecorrelatedJitter {
+    /// Create a policy with `base` and `cap` delays
+    /// making at most `max_attempts` attempts.
+    pub fn new(base: Duration, cap: Duration, max_attempts: u32) -> Self {
+        Self {
+            base,
+            cap,
+            max_attempts,
+        }
+    }
+}
+
+impl RetryPolicy for DecorrelatedJitter {
+    fn next_delay(&self, attempt: u32, status: Option<StatusCode>) -> Option<Duration> {
+        if attempt >= self.max_attempts || !is_transient(status) {
+            return None;
+        }
+        let low = self.base.as_millis() as u64;
+        let high = low
+            .saturating_mul(3u64.saturating_pow(attempt))
+            .min(self.cap.as_millis() as u64)
+            .max(low + 1);
+        let delay = Duration::from_millis(low + random() % (high - low)).min(self.cap);
+        Some(delay)
+    }
+}
+
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
@@ -0,0 +1,37 @@
+//! Replacing control characters before checking.
+
+use crate::offset::TextMap;
+
+/// Whether `c` is replaced by [`sanitize_control_chars`](crate::Client::sanitize_control_chars):
+/// a C0 control (U+0000 to U+001F), DEL (U+007F) or a C1 control
+/// (U+0080 to U+009F), except line feeds and tabs.
+fn is_replaced(c: char) -> bool {
+    c.is_control() && c != '\n' && c != '\t'
+}
+
+/// Replace each control character in `text` with a space.
+pub(crate) fn control_chars(text: &str) -> TextMap {
+    TextMap::rewrite(runs(text), |run| {
+        if run.starts_with(is_replaced) {
+            " ".repeat(run.chars().count())
+        } else {
+            run.to_string()
+        }
+    })
+}
+
+/// Split `text` into runs of replaced and other characters.
+fn runs(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let kind = is_replaced(first);
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| is_replaced(c) != kind)
+            .map_or(rest.len(), |(index, _)| index);
+        let (run, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(run)
+    })
+}
@@ -0,0 +1,104 @@
+//! Splitting the checked text into plain and flagged runs.
+
+use crate::types::Match;
+use crate::Response;
+
+/// A run of the checked text, see [`context_map`](Response::context_map).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment<'a> {
+    /// Text without any match.
+    Plain(&'a str),
+    /// Text flagged by a match.
+    Flagged {
+        /// The flagged text.
+        text: &'a str,
+        /// The match flagging it.
+        match_ref: &'a Match,
+    },
+}
+
+impl<'a> Segment<'a> {
+    /// The text of the segment.
+    pub fn text(&self) -> &'a str {
+        match self {
+            Segment::Plain(text) | Segment::Flagged { text, .. } => text,
+        }
+    }
+}
+
+impl Response {
+    /// Split `text` into plain and flagged segments, in order.
+    ///
+    /// The segments cover the whole text. Where matches overlap,
+    /// the text is split at every match boundary, and each piece is
+    /// attributed to the earliest match covering it. Empty matches
+    /// and matches that don't fit `text` are left out.
+    ///
+    /// ```no_run
+    /// # use grammarbot::{Client, Segment};
+    /// # let client = Client::new("your_api_key");
+    /// let text = "I can't remember how to go their.";
+    /// let response = client.check(text)?;
+    /// for segment in response.context_map(text) {
+    ///     match segment {
+    ///         Segment::Plain(text) => print!("{}", text),
+    ///         Segment::Flagged { text, .. } => print!("**{}**", text),
+    ///     }
+    /// }
+    /// # Ok::<(), grammarbot::Error>(())
+    /// ```
+    pub fn context_map<'a>(&'a self, text: &'a str) -> Vec<Segment<'a>> {
+        let mut spans: Vec<_> = self
+            .matches
+            .iter()
+            .filter_map(|m| Some((m.byte_range(text)?, m)))
+            .filter(|(range, _)| !range.is_empty())
+            .collect();
+        spans.sort_by_key(|(range, _)| (range.start, range.end));
+
+        let mut boundaries: Vec<_> = spans
+            .iter()
+            .flat_map(|(range, _)| vec![range.start, range.end])
+            .chain(vec![0, text.len()])
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let mut segments: Vec<Segment<'a>> = Vec::new();
+        for pair in boundaries.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            let piece = &text[start..end];
+            let covering = spans
+                .iter()
+                .find(|(range, _)| range.start <= start && end <= range.end)
+                .map(|(_, m)| *m);
+            let merged = match (segments.last_mut(), covering) {
+                (Some(Segment::Plain(last)), None) => {
+                    *last = &text[start - last.len()..end];
+                    true
+                }
+                (
+                    Some(Segment::Flagged {
+                        text: last,
+                        match_ref,
+                    }),
+                    Some(m),
+                ) if std::ptr::eq(*match_ref, m) => {
+                    *last = &text[start - last.len()..end];
+                    true
+                }
+                _ => false,
+            };
+            if !merged {
+                segments.push(match covering {
+                    Some(m) => Segment::Flagged {
+                        text: piece,
+                        match_ref: m,
+                    },
+                    None => Segment::Plain(piece),
+                });
+            }
+        }
+        segments
+    }
+}
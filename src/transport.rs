@@ -0,0 +1,152 @@
+//! The HTTP layer under the client.
+//!
+//! A [`Transport`] can be set with [`transport`](crate::Client::transport),
+//! e.g. to use another HTTP library or to answer requests in-process in tests.
+//! By default, requests are sent with `reqwest`.
+
+use crate::{Error, Result};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
+use std::collections::HashMap;
+use std::net::ToSocketAddrs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A response received by a [`Transport`], with its body read.
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// The response status.
+    pub status: StatusCode,
+    /// The response headers, used to check the `Content-Type`.
+    pub headers: HeaderMap,
+    /// The decoded, decompressed response body.
+    pub body: String,
+}
+
+/// Sends HTTP requests for a client.
+pub trait Transport: Send + Sync {
+    /// Send a request and read its response, successful or not.
+    ///
+    /// `query` is to be appended to `url`, and `headers` are the client's
+    /// [headers](crate::Client::header), including its `User-Agent`.
+    /// `timeout` is the client's [`timeout`](crate::Client::timeout)
+    /// for the attempt, if any.
+    /// An `Err` means no response was received, and may be retried
+    /// according to the client's retry policy.
+    fn send(
+        &self,
+        method: Method,
+        url: &Url,
+        query: &[(&str, String)],
+        headers: &HeaderMap,
+        body: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<RawResponse>;
+}
+
+/// Send requests with a blocking `reqwest` client. This is the default.
+///
+/// `reqwest` sets timeouts per client, so requests with a timeout are sent
+/// with another client, built once for each timeout and kept along with
+/// its connection pool. Clones share all of these clients.
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    with_timeouts: Arc<Mutex<HashMap<Duration, reqwest::Client>>>,
+}
+
+impl ReqwestTransport {
+    /// Create a transport with `reqwest`'s default settings.
+    pub fn new() -> Self {
+        Self::from_client(reqwest::Client::new())
+    }
+
+    /// Create a transport with a timeout for each request,
+    /// unless the request sets its own.
+    pub fn with_timeout(timeout: Duration) -> Result<Self> {
+        Ok(Self::from_client(client_with_timeout(timeout)?))
+    }
+
+    fn from_client(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            with_timeouts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The client for requests with `timeout`.
+    fn client(&self, timeout: Option<Duration>) -> Result<reqwest::Client> {
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => return Ok(self.client.clone()),
+        };
+        let mut clients = self.with_timeouts.lock().unwrap();
+        if let Some(client) = clients.get(&timeout) {
+            return Ok(client.clone());
+        }
+        let client = client_with_timeout(timeout)?;
+        clients.insert(timeout, client.clone());
+        Ok(client)
+    }
+}
+
+fn client_with_timeout(timeout: Duration) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|source| Error::RequestFailed { source })
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(
+        &self,
+        method: Method,
+        url: &Url,
+        query: &[(&str, String)],
+        headers: &HeaderMap,
+        body: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<RawResponse> {
+        let mut request = self
+            .client(timeout)?
+            .request(method, url.clone())
+            .headers(headers.clone())
+            .query(query);
+        if let Some(body) = body {
+            request = request.body(body.to_string());
+        }
+        let mut response = request
+            .send()
+            .map_err(|source| request_failed(url, source))?;
+        Ok(RawResponse {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response
+                .text()
+                .map_err(|source| Error::RequestFailed { source })?,
+        })
+    }
+}
+
+/// Classify a failed request, telling name-resolution failures apart.
+///
+/// `reqwest` doesn't expose why a connection failed, so the host is
+/// resolved again to check whether that is the cause.
+fn request_failed(url: &Url, source: reqwest::Error) -> Error {
+    if let (Some(host), false) = (url.host_str(), source.is_timeout()) {
+        let port = url.port_or_known_default().unwrap_or(80);
+        if let Err(resolution) = (host, port).to_socket_addrs() {
+            return Error::DnsFailure {
+                host: host.to_string(),
+                source: resolution,
+            };
+        }
+    }
+    Error::RequestFailed { source }
+}
@@ -0,0 +1,11 @@
+extern crate grammarbot;
+use grammarbot::{Client, Error, Response};
+
+fn _assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_send_sync() {
+    _assert_send_sync::<Client>();
+    _assert_send_sync::<Response>();
+    _assert_send_sync::<Error>();
+}
@@ -0,0 +1,1224 @@
+extern crate grammarbot;
+mod common;
+
+use common::{match_json, response_json, response_json_detected, FakeTransport, MockServer, Reply};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use grammarbot::retry::{Constant, DecorrelatedJitter, RetryPolicy};
+use grammarbot::{
+    CheckOptions, ChunkStrategy, CjkSplitter, Client, Error, RateLimiter, Result, RuleParamStyle,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[test]
+fn test_check_retrying_language_detection() -> Result<()> {
+    let server = MockServer::start(|request| match request.param("language") {
+        Some("auto") => Reply::json(&response_json_detected(
+            vec![match_json(0, 2, "MORFOLOGIK_RULE_EN_US", "TYPOS")],
+            "English",
+            "en",
+        )),
+        Some("fr-FR") => Reply::json(&response_json(vec![])),
+        _ => Reply::json(&response_json(vec![
+            match_json(0, 2, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+            match_json(3, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        ])),
+    });
+    let mut client = Client::new("test");
+    client.language("auto").base(server.url())?;
+
+    let response = client.check_retrying_language_detection("Je suis", &["en-US", "fr-FR"])?;
+    assert!(response.matches.is_empty());
+    let languages: Vec<_> = server
+        .requests()
+        .iter()
+        .map(|request| request.param("language").unwrap().to_string())
+        .collect();
+    assert_eq!(vec!["auto", "en-US", "fr-FR"], languages);
+    Ok(())
+}
+
+#[test]
+fn test_validate_config() -> Result<()> {
+    let server = MockServer::json(json!([
+        { "name": "English (US)", "code": "en", "longCode": "en-US" },
+        { "name": "German (Germany)", "code": "de", "longCode": "de-DE" },
+    ]));
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+    client.validate_config()?;
+
+    client.language("xx-XX");
+    match client.validate_config() {
+        Err(Error::UnsupportedLanguage {
+            requested,
+            supported,
+        }) => {
+            assert_eq!("xx-XX", requested);
+            assert_eq!(vec!["en-US", "de-DE"], supported);
+        }
+        other => panic!("expected UnsupportedLanguage, got {:?}", other),
+    }
+    assert_eq!("/v2/languages", server.requests()[0].path);
+    Ok(())
+}
+
+#[test]
+fn test_retry_policy() -> Result<()> {
+    struct TwoAttempts;
+    impl RetryPolicy for TwoAttempts {
+        fn next_delay(&self, attempt: u32, status: Option<StatusCode>) -> Option<Duration> {
+            assert_eq!(Some(StatusCode::SERVICE_UNAVAILABLE), status);
+            if attempt < 2 {
+                Some(Duration::from_millis(10))
+            } else {
+                None
+            }
+        }
+    }
+
+    let server = MockServer::start(|_| Reply::status(503));
+    let mut client = Client::new("test");
+    client.base(server.url())?.retry_policy(TwoAttempts);
+    match client.check("text") {
+        Err(Error::Api { status, .. }) => assert_eq!(StatusCode::SERVICE_UNAVAILABLE, status),
+        other => panic!("expected Api error, got {:?}", other),
+    }
+    assert_eq!(2, server.requests().len());
+    Ok(())
+}
+
+#[test]
+fn test_decorrelated_jitter() {
+    let base = Duration::from_millis(100);
+    let cap = Duration::from_secs(2);
+    let policy = DecorrelatedJitter::new(base, cap, 5);
+    let unavailable = Some(StatusCode::SERVICE_UNAVAILABLE);
+    for _ in 0..100 {
+        // Interleaved requests on the same policy are bounded by
+        // their own attempt numbers.
+        for (attempt, high) in [(1, 300), (3, 2000), (1, 300), (2, 900)] {
+            let delay = policy.next_delay(attempt, unavailable).unwrap();
+            assert!(delay >= base && delay <= Duration::from_millis(high));
+        }
+    }
+    assert_eq!(None, policy.next_delay(5, unavailable));
+    assert_eq!(None, policy.next_delay(1, Some(StatusCode::BAD_REQUEST)));
+}
+
+#[test]
+fn test_defaults() -> Result<()> {
+    let server = MockServer::json(response_json(vec![]));
+    let mut client = Client::new("test");
+    client.base(server.url())?.defaults(CheckOptions {
+        picky: Some(true),
+        disabled_rules: vec!["WHITESPACE_RULE".to_string()],
+        ..CheckOptions::default()
+    });
+
+    client.check("text")?;
+    client.check_with(
+        "text",
+        &CheckOptions {
+            language: Some("de-DE".to_string()),
+            picky: Some(false),
+            ..CheckOptions::default()
+        },
+    )?;
+
+    let requests = server.requests();
+    assert_eq!(Some("en-US"), requests[0].param("language"));
+    assert_eq!(Some("picky"), requests[0].param("level"));
+    assert_eq!(Some("WHITESPACE_RULE"), requests[0].param("disabledRules"));
+    assert_eq!(Some("de-DE"), requests[1].param("language"));
+    assert_eq!(Some("default"), requests[1].param("level"));
+    assert_eq!(Some("WHITESPACE_RULE"), requests[1].param("disabledRules"));
+    Ok(())
+}
+
+#[test]
+fn test_check_chunked_cancellable() -> Result<()> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    let server = MockServer::start(move |_| {
+        flag.store(true, Ordering::SeqCst);
+        Reply::json(&response_json(vec![match_json(
+            0,
+            3,
+            "UPPERCASE_SENTENCE_START",
+            "CASING",
+        )]))
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let text = "one sentence. two sentence. three sentence.";
+    match client.check_chunked_cancellable(text, 15, &cancel) {
+        Err(Error::Cancelled {
+            partial: Some(partial),
+        }) => assert_eq!(1, partial.matches.len()),
+        other => panic!("expected Cancelled, got {:?}", other),
+    }
+    assert_eq!(1, server.requests().len());
+    assert_eq!(Some("one sentence. "), server.requests()[0].param("text"));
+    Ok(())
+}
+
+#[test]
+fn test_chunk_strategy_sentence_with_overlap() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let text = request.param("text").unwrap();
+        let mut matches = Vec::new();
+        if let Some(offset) = text.find("their") {
+            matches.push(match_json(
+                offset as u32,
+                5,
+                "CONFUSION_RULE",
+                "CONFUSED_WORDS",
+            ));
+        }
+        if let Some(offset) = text.find("their. There") {
+            matches.push(match_json(
+                offset as u32,
+                12,
+                "CROSS_SENTENCE_RULE",
+                "STYLE",
+            ));
+        }
+        Reply::json(&response_json(matches))
+    });
+    let mut client = Client::new("test");
+    client
+        .base(server.url())?
+        .chunk_strategy(ChunkStrategy::SentenceWithOverlap(1));
+
+    let text = "Start here. I went their. There it was.";
+    let response = client.check_chunked(text, 27)?;
+    let texts: Vec<_> = server
+        .requests()
+        .iter()
+        .map(|request| request.param("text").unwrap().to_string())
+        .collect();
+    assert_eq!(
+        vec!["Start here. I went their. ", "I went their. There it was."],
+        texts
+    );
+    let found: Vec<_> = response
+        .matches
+        .iter()
+        .map(|m| (m.rule.id.as_str(), m.offset))
+        .collect();
+    assert_eq!(
+        vec![("CONFUSION_RULE", 19), ("CROSS_SENTENCE_RULE", 19)],
+        found
+    );
+    Ok(())
+}
+
+#[test]
+fn test_check_paths() -> Result<()> {
+    let server = MockServer::json(response_json(vec![match_json(0, 4, "RULE", "TYPOS")]));
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let readable = std::env::temp_dir().join(format!("grammarbot-{}.txt", std::process::id()));
+    std::fs::write(&readable, "Some text.").unwrap();
+    let missing = PathBuf::from("/nonexistent/grammarbot.txt");
+    let results = client.check_paths(&[readable.clone(), missing.clone()]);
+    std::fs::remove_file(&readable).unwrap();
+
+    assert_eq!(2, results.len());
+    assert_eq!(readable, results[0].0);
+    assert_eq!(1, results[0].1.as_ref().unwrap().matches.len());
+    assert_eq!(missing, results[1].0);
+    match &results[1].1 {
+        Err(Error::Io { path, .. }) => assert_eq!(&missing, path),
+        other => panic!("expected Io error, got {:?}", other),
+    }
+    assert_eq!(Some("Some text."), server.requests()[0].param("text"));
+    Ok(())
+}
+
+#[test]
+fn test_fail_on_incomplete() -> Result<()> {
+    let mut incomplete = response_json(vec![match_json(0, 4, "RULE", "TYPOS")]);
+    incomplete["warnings"]["incompleteResults"] = true.into();
+    let server = MockServer::json(incomplete);
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+    assert!(client.check("text")?.warnings.incomplete_results);
+
+    client.fail_on_incomplete(true);
+    match client.check("text") {
+        Err(Error::IncompleteResults { response }) => assert_eq!(1, response.matches.len()),
+        other => panic!("expected IncompleteResults, got {:?}", other),
+    }
+
+    client.allow_incomplete(true);
+    assert!(client.check("text").is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_check_with_meta() -> Result<()> {
+    let calls = AtomicUsize::new(0);
+    let server = MockServer::start(move |_| {
+        if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+            Reply::status(429)
+        } else {
+            Reply::json(&response_json(vec![]))
+        }
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?.retry_policy(Constant {
+        delay: Duration::from_millis(10),
+        max_attempts: 3,
+    });
+
+    let (_, meta) = client.check_with_meta("text")?;
+    assert_eq!(2, meta.attempts);
+    assert_eq!(StatusCode::OK, meta.status);
+    assert!(meta.elapsed >= Duration::from_millis(10));
+    Ok(())
+}
+
+#[test]
+fn test_quota_exceeded_reset_at() -> Result<()> {
+    let server = MockServer::start(|_| {
+        Reply::status(429)
+            .header("X-RateLimit-Reset", "4102444800")
+            .body("daily quota exceeded")
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    match client.check("text") {
+        Err(Error::QuotaExceeded { reset_at, body }) => {
+            assert_eq!(
+                Some(UNIX_EPOCH + Duration::from_secs(4_102_444_800)),
+                reset_at
+            );
+            assert_eq!("daily quota exceeded", body);
+        }
+        other => panic!("expected QuotaExceeded, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_fallback_base() -> Result<()> {
+    let primary = MockServer::start(|_| Reply::status(503));
+    let fallback = MockServer::json(response_json(vec![match_json(0, 4, "RULE", "TYPOS")]));
+    let mut client = Client::new("test");
+    client
+        .base(primary.url())?
+        .fallback_base(fallback.url())?
+        .retry_policy(Constant {
+            delay: Duration::from_millis(1),
+            max_attempts: 2,
+        });
+
+    let (response, meta) = client.check_with_meta("text")?;
+    assert_eq!(1, response.matches.len());
+    assert_eq!(StatusCode::OK, meta.status);
+    assert_eq!(3, meta.attempts);
+    assert_eq!(fallback.url(), meta.base.as_str().trim_end_matches('/'));
+    assert_eq!(2, primary.requests().len());
+    assert_eq!("/v2/check", fallback.requests()[0].path);
+    Ok(())
+}
+
+#[test]
+fn test_wait_for_rate_limit_reset() -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let reset = Arc::new(Mutex::new(now - 10));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let server = MockServer::start({
+        let reset = Arc::clone(&reset);
+        let calls = Arc::clone(&calls);
+        move |_| {
+            if calls.fetch_add(1, Ordering::SeqCst) != 1 {
+                let reset = reset.lock().unwrap().to_string();
+                Reply::status(429).header("X-RateLimit-Reset", &reset)
+            } else {
+                Reply::json(&response_json(vec![]))
+            }
+        }
+    });
+    let mut client = Client::new("test");
+    client
+        .base(server.url())?
+        .retry_policy(Constant {
+            delay: Duration::from_secs(60),
+            max_attempts: 2,
+        })
+        .wait_for_rate_limit_reset(Duration::from_secs(5));
+
+    let start = Instant::now();
+    let (_, meta) = client.check_with_meta("text")?;
+    assert_eq!(2, meta.attempts);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    *reset.lock().unwrap() = now + 3600;
+    let start = Instant::now();
+    assert!(matches!(
+        client.check("text"),
+        Err(Error::QuotaExceeded {
+            reset_at: Some(_),
+            ..
+        })
+    ));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(3, calls.load(Ordering::SeqCst));
+    Ok(())
+}
+
+#[cfg(feature = "nfc")]
+#[test]
+fn test_normalize_nfc() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let text = request.param("text").unwrap();
+        let offset = text.encode_utf16().count() as u32 - 4;
+        Reply::json(&response_json(vec![match_json(offset, 4, "RULE", "TYPOS")]))
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?.normalize_nfc(true);
+
+    let text = "Cafe\u{301} bar.";
+    let response = client.check(text)?;
+    assert_eq!(Some("Caf\u{e9} bar."), server.requests()[0].param("text"));
+    let m = &response.matches[0];
+    assert_eq!((6, 4), (m.offset, m.length));
+    assert_eq!("bar.", &text[m.byte_range(text).unwrap()]);
+    Ok(())
+}
+
+#[test]
+fn test_check_batched_single_request() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let text = request.param("text").unwrap();
+        let matches = ["Exti", "teh"]
+            .iter()
+            .filter_map(|typo| text.find(typo).map(|offset| (offset, typo.len())))
+            .map(|(offset, length)| match_json(offset as u32, length as u32, "TYPO", "TYPOS"))
+            .collect();
+        Reply::json(&response_json(matches))
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let matches = client.check_batched_single_request(&["Open teh file", "Save as", "Exti"])?;
+    assert_eq!(
+        Some("Open teh file\n\nSave as\n\nExti"),
+        server.requests()[0].param("text")
+    );
+    assert_eq!(3, matches.len());
+    assert_eq!(
+        vec![5],
+        matches[0].iter().map(|m| m.offset).collect::<Vec<_>>()
+    );
+    assert!(matches[1].is_empty());
+    assert_eq!(
+        vec![0],
+        matches[2].iter().map(|m| m.offset).collect::<Vec<_>>()
+    );
+
+    assert!(client.check_batched_single_request(&[])?.is_empty());
+    assert_eq!(1, server.requests().len());
+    Ok(())
+}
+
+#[test]
+fn test_gzipped_html_error_page() -> Result<()> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(b"<html><body>Service unavailable</body></html>")
+        .unwrap();
+    let body = encoder.finish().unwrap();
+    let server = MockServer::start(move |_| {
+        Reply::status(200)
+            .header("Content-Type", "text/html; charset=utf-8")
+            .header("Content-Encoding", "gzip")
+            .body(body.clone())
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+    match client.check("text") {
+        Err(Error::UnexpectedContentType { content_type }) => {
+            assert_eq!("text/html; charset=utf-8", content_type)
+        }
+        other => panic!("expected UnexpectedContentType, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_check_ignoring() -> Result<()> {
+    let server = MockServer::json(response_json(vec![
+        match_json(6, 10, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(18, 5, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+    ]));
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let text = "Call `frobnicate` twcie.";
+    let response = client.check_ignoring(text, &[0..4, 5..17])?;
+    assert_eq!(
+        vec![18],
+        response
+            .matches
+            .iter()
+            .map(|m| m.offset)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(Some(text), server.requests()[0].param("text"));
+    Ok(())
+}
+
+#[test]
+fn test_check_with_language_override_per_paragraph() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let text = request.param("text").unwrap();
+        if text.contains("acuerdo") {
+            let offset = text.find("acuerdo").unwrap() as u32;
+            Reply::json(&response_json_detected(
+                vec![match_json(offset, 7, "ES_RULE", "GRAMMAR")],
+                "Spanish",
+                "es",
+            ))
+        } else {
+            Reply::json(&response_json_detected(vec![], "English (US)", "en-US"))
+        }
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let text = "I can't remember.\n\nNo me acuerdo.";
+    let (response, languages) = client.check_with_language_override_per_paragraph(text)?;
+    let codes: Vec<_> = languages
+        .iter()
+        .map(|paragraph| (paragraph.range.clone(), paragraph.language.code.as_str()))
+        .collect();
+    assert_eq!(vec![(0..18, "en-US"), (19..33, "es")], codes);
+    assert_eq!(
+        vec![25],
+        response
+            .matches
+            .iter()
+            .map(|m| m.offset)
+            .collect::<Vec<_>>()
+    );
+    assert!(server
+        .requests()
+        .iter()
+        .all(|request| request.param("language") == Some("auto")));
+    Ok(())
+}
+
+#[test]
+fn test_language_override_per_paragraph_fallback() -> Result<()> {
+    let server =
+        MockServer::start(
+            |request| match (request.param("text"), request.param("language")) {
+                (Some("Intro\n"), Some("auto")) => {
+                    Reply::json(&response_json_detected(vec![], "", "zz"))
+                }
+                (_, Some("auto")) => Reply::json(&response_json_detected(vec![], "Spanish", "es")),
+                _ => Reply::json(&response_json(vec![])),
+            },
+        );
+    let mut client = Client::new("test");
+    client.base(server.url())?.language("en-GB");
+
+    let text = "Intro\n\nNo me acuerdo.";
+    let (_, languages) = client.check_with_language_override_per_paragraph(text)?;
+    let codes: Vec<_> = languages
+        .iter()
+        .map(|paragraph| (paragraph.language.code.as_str(), paragraph.fallback))
+        .collect();
+    assert_eq!(vec![("en-US", true), ("es", false)], codes);
+    assert_eq!(Some("en-GB"), server.requests()[1].param("language"));
+
+    client.language("auto");
+    assert!(matches!(
+        client.check_with_language_override_per_paragraph(text),
+        Err(Error::LanguageUndetected)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_ignore_words_with_punctuation() -> Result<()> {
+    let server = MockServer::json(response_json(vec![
+        match_json(7, 10, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(22, 6, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+    ]));
+    let mut client = Client::new("test");
+    client.base(server.url())?.ignore_words(&["Rustacean"]);
+
+    let response = client.check("Hello, Rustacean, and welcme.")?;
+    assert_eq!(
+        vec![22],
+        response
+            .matches
+            .iter()
+            .map(|m| m.offset)
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rule_param_style() -> Result<()> {
+    let server = MockServer::json(response_json(vec![]));
+    let mut client = Client::new("test");
+    client.base(server.url())?.defaults(CheckOptions {
+        disabled_rules: vec!["A".to_string(), "B".to_string()],
+        ..CheckOptions::default()
+    });
+    client.check("text")?;
+    client
+        .rule_param_style(RuleParamStyle::Repeated)
+        .check("text")?;
+
+    let disabled = |index: usize| -> Vec<String> {
+        server.requests()[index]
+            .query
+            .iter()
+            .filter(|(name, _)| name == "disabledRules")
+            .map(|(_, value)| value.clone())
+            .collect()
+    };
+    assert_eq!(vec!["A,B"], disabled(0));
+    assert_eq!(vec!["A", "B"], disabled(1));
+    Ok(())
+}
+
+#[test]
+fn test_strict_profile() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let mut response = response_json(vec![]);
+        match request.param("text") {
+            Some("incomplete") => response["warnings"]["incompleteResults"] = true.into(),
+            Some("extra") => response["software"]["extra"] = "field".into(),
+            _ => {}
+        }
+        Reply::json(&response)
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+    assert!(client.check("extra").is_ok());
+
+    client.strict_profile();
+    assert!(client.check("fine").is_ok());
+    match client.check("incomplete") {
+        Err(Error::IncompleteResults { .. }) => {}
+        other => panic!("expected IncompleteResults, got {:?}", other),
+    }
+    match client.check("extra") {
+        Err(Error::UnknownFields { fields }) => assert_eq!(vec!["software.extra"], fields),
+        other => panic!("expected UnknownFields, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_shared_limiter() -> Result<()> {
+    let server = MockServer::json(response_json(vec![]));
+    let limiter = Arc::new(RateLimiter::new(10, Duration::from_secs(1)));
+    let mut english = Client::new("test");
+    english.base(server.url())?.shared_limiter(limiter.clone());
+    let mut german = Client::new("test");
+    german
+        .language("de-DE")
+        .base(server.url())?
+        .shared_limiter(limiter);
+
+    let start = Instant::now();
+    for _ in 0..2 {
+        english.check("text")?;
+        german.check("text")?;
+    }
+    assert_eq!(4, server.requests().len());
+    assert!(start.elapsed() >= Duration::from_millis(300));
+    Ok(())
+}
+
+#[test]
+fn test_expand_contractions() -> Result<()> {
+    let server = MockServer::json(response_json(vec![
+        match_json(2, 6, "CANNOT_RULE", "STYLE"),
+        match_json(28, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+    ]));
+    let mut client = Client::new("test");
+    client.base(server.url())?.expand_contractions(true);
+
+    let text = "I can't remember how to go their.";
+    let response = client.check(text)?;
+    assert_eq!(
+        Some("I cannot remember how to go their."),
+        server.requests()[0].param("text")
+    );
+    let spans: Vec<_> = response
+        .matches
+        .iter()
+        .map(|m| &text[m.byte_range(text).unwrap()])
+        .collect();
+    assert_eq!(vec!["can't", "their"], spans);
+    Ok(())
+}
+
+#[test]
+fn test_cjk_splitter() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let text = request.param("text").unwrap_or_default();
+        let matches = match text.find("公园") {
+            Some(index) => {
+                let offset = text[..index].encode_utf16().count() as u32;
+                vec![match_json(offset, 2, "PARK_RULE", "STYLE")]
+            }
+            None => Vec::new(),
+        };
+        Reply::json(&response_json(matches))
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let text = "今天天气很好。我们去公园吧！他说的对吗？";
+    let response = client.check_with_custom_sentence_splitter(text, 30, &CjkSplitter)?;
+    let texts: Vec<_> = server
+        .requests()
+        .iter()
+        .map(|request| request.param("text").unwrap().to_string())
+        .collect();
+    assert_eq!(
+        vec!["今天天气很好。", "我们去公园吧！", "他说的对吗？"],
+        texts
+    );
+    assert_eq!(1, response.matches.len());
+    assert_eq!(10, response.matches[0].offset);
+    let span = response.matches[0].byte_range(text).unwrap();
+    assert_eq!("公园", &text[span]);
+    Ok(())
+}
+
+#[test]
+fn test_default_ignored_categories() -> Result<()> {
+    let server = MockServer::json(response_json(vec![
+        match_json(0, 1, "QUOTES", "TYPOGRAPHY"),
+        match_json(5, 1, "DASHES", "TYPOGRAPHY"),
+    ]));
+    let mut client = Client::new("test");
+    client
+        .base(server.url())?
+        .set_default_ignored_categories(&["TYPOGRAPHY"]);
+
+    let text = "\"Hi\" - there";
+    let response = client.check(text)?;
+    assert_eq!(2, response.matches.len());
+    assert!(response.is_clean());
+    assert_eq!(0, response.relevant_matches().count());
+    assert_eq!(
+        "## GrammarBot: no issues found\n",
+        response.to_markdown_report(text)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dns_failure() -> Result<()> {
+    let mut client = Client::new("test");
+    client.base("http://grammarbot.invalid")?;
+    match client.check("I can't remember how to go their.") {
+        Err(Error::DnsFailure { host, .. }) => assert_eq!("grammarbot.invalid", host),
+        other => panic!("expected a DNS failure, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_retry_budget() -> Result<()> {
+    let server = MockServer::start(|_| Reply::status(503));
+    let mut client = Client::new("test");
+    client
+        .base(server.url())?
+        .retry_policy(Constant {
+            delay: Duration::from_millis(1),
+            max_attempts: 3,
+        })
+        .retry_budget(3);
+
+    let mut attempts = Vec::new();
+    for text in &["one", "two", "three"] {
+        let before = server.requests().len();
+        assert!(client.check(text).is_err());
+        attempts.push(server.requests().len() - before);
+    }
+    assert_eq!(vec![3, 2, 1], attempts);
+    Ok(())
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn test_check_encoded() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let text = request.param("text").unwrap();
+        let mut matches: Vec<_> = ["Its\u{201d}", "their"]
+            .iter()
+            .map(|word| {
+                let start = text.find(word).unwrap();
+                let offset = text[..start].encode_utf16().count() as u32;
+                let length = word.encode_utf16().count() as u32;
+                match_json(offset, length, "RULE", "GRAMMAR")
+            })
+            .collect();
+        matches.push(match_json(100, 4, "OUT_OF_RANGE", "GRAMMAR"));
+        Reply::json(&response_json(matches))
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let bytes = b"\x93Its\x94 their car.";
+    let response = client.check_encoded(bytes, "windows-1252")?;
+    assert_eq!(
+        Some("\u{201c}Its\u{201d} their car."),
+        server.requests()[0].param("text")
+    );
+    let spans: Vec<_> = response
+        .matches
+        .iter()
+        .map(|m| &bytes[m.offset as usize..(m.offset + m.length) as usize])
+        .collect();
+    assert_eq!(vec![&b"Its\x94"[..], &b"their"[..]], spans);
+    assert_eq!(1, response.skipped_matches());
+
+    let utf16: Vec<u8> = "\u{201c}Its\u{201d} their car."
+        .encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes().to_vec())
+        .collect();
+    let response = client.check_encoded(&utf16, "utf-16le")?;
+    let m = &response.matches[1];
+    assert_eq!((12, 10), (m.offset, m.length));
+
+    match client.check_encoded(bytes, "klingon") {
+        Err(Error::UnknownEncoding { encoding }) => assert_eq!("klingon", encoding),
+        other => panic!("expected UnknownEncoding, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_check_all_input_failed() -> Result<()> {
+    let server = MockServer::start(|request| match request.param("text") {
+        Some("bad") => Reply::status(500),
+        _ => Reply::json(&response_json(vec![])),
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let results = client.check_all(&["good", "bad", "fine"]);
+    assert_eq!(3, results.len());
+    assert!(results[0].is_ok());
+    assert!(results[2].is_ok());
+    match &results[1] {
+        Err(Error::InputFailed { index, source }) => {
+            assert_eq!(1, *index);
+            assert!(matches!(**source, Error::Api { .. }));
+        }
+        other => panic!("expected InputFailed, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_user_agent_suffix() -> Result<()> {
+    let server = MockServer::json(response_json(vec![]));
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+    client.check("text")?;
+    client.user_agent_suffix("my-docs-linter/2.0")?;
+    client.check("text")?;
+
+    let requests = server.requests();
+    assert_eq!(
+        Some(grammarbot::USER_AGENT),
+        requests[0].header("User-Agent")
+    );
+    assert_eq!(
+        Some(
+            format!(
+                "my-docs-linter/2.0 grammarbot-rs/{}",
+                env!("CARGO_PKG_VERSION")
+            )
+            .as_str()
+        ),
+        requests[1].header("User-Agent")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_language_undetected() -> Result<()> {
+    let server = MockServer::json(response_json_detected(vec![], "Unknown", "zz"));
+    let mut client = Client::new("test");
+    client.base(server.url())?.language("auto");
+    assert!(matches!(client.check("ok"), Err(Error::LanguageUndetected)));
+
+    let server = MockServer::start(|_| {
+        Reply::status(400).body("Error: Could not detect language of text 'ok'")
+    });
+    client.base(server.url())?;
+    assert!(matches!(client.check("ok"), Err(Error::LanguageUndetected)));
+
+    client.language("en-US");
+    assert!(matches!(client.check("ok"), Err(Error::Api { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_base_url() -> Result<()> {
+    let server = MockServer::json(response_json(vec![]));
+    let url = reqwest::Url::parse(server.url()).unwrap();
+    let mut client = Client::new("test");
+    client.base_url(url);
+    client.check("text")?;
+    assert_eq!("/v2/check", server.requests()[0].path);
+    Ok(())
+}
+
+#[test]
+fn test_transport() -> Result<()> {
+    let transport = FakeTransport::json(response_json(vec![match_json(
+        27,
+        5,
+        "CONFUSION_RULE",
+        "CONFUSED_WORDS",
+    )]));
+    let mut client = Client::new("test");
+    client.transport(transport.clone());
+    let response = client.check("I can't remember how to go their.")?;
+    assert_eq!("CONFUSION_RULE", response.matches[0].rule.id);
+
+    let requests = transport.requests();
+    assert_eq!(1, requests.len());
+    assert_eq!(reqwest::Method::GET, requests[0].method);
+    assert_eq!(
+        "http://api.grammarbot.io/v2/check",
+        requests[0].url.as_str()
+    );
+    assert_eq!(
+        Some("I can't remember how to go their."),
+        requests[0].param("text")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_strict_profile_keeps_transport() -> Result<()> {
+    let transport = FakeTransport::json(response_json(vec![]));
+    let mut client = Client::new("test");
+    client.transport(transport.clone());
+    client.check("text")?;
+    client.strict_profile();
+    client.check("text")?;
+    let timeouts: Vec<_> = transport
+        .requests()
+        .iter()
+        .map(|request| request.timeout)
+        .collect();
+    assert_eq!(vec![None, Some(Duration::from_secs(30))], timeouts);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_rule_config() -> Result<()> {
+    let server = MockServer::json(response_json(vec![]));
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let nothing = CheckOptions {
+        enabled_only: Some(true),
+        disabled_rules: vec!["WHITESPACE_RULE".to_string()],
+        ..CheckOptions::default()
+    };
+    assert!(matches!(
+        client.check_with("text", &nothing),
+        Err(Error::InvalidRuleConfig)
+    ));
+    assert!(server.requests().is_empty());
+
+    let some = CheckOptions {
+        enabled_rules: vec!["EN_A_VS_AN".to_string()],
+        ..nothing.clone()
+    };
+    client.check_with("text", &some)?;
+    assert_eq!(1, server.requests().len());
+
+    client.defaults(nothing);
+    assert!(matches!(
+        client.validate_config(),
+        Err(Error::InvalidRuleConfig)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_check_multi_language() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let matches = match request.param("language") {
+            Some("fr-FR") => vec![],
+            Some("de-DE") => vec![match_json(0, 2, "GERMAN_SPELLER_RULE", "TYPOS")],
+            _ => vec![
+                match_json(0, 2, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+                match_json(3, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+            ],
+        };
+        Reply::json(&response_json(matches))
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let (language, response) =
+        client.check_multi_language("Je suis", &["en-US", "fr-FR", "de-DE"])?;
+    assert_eq!("fr-FR", language);
+    assert!(response.matches.is_empty());
+    assert_eq!(3, server.requests().len());
+
+    let most_matches = |response: &grammarbot::Response| -(response.matches.len() as f64);
+    let (language, _) =
+        client.check_multi_language_with("Je suis", &["fr-FR", "en-US"], &most_matches)?;
+    assert_eq!("en-US", language);
+    Ok(())
+}
+
+#[test]
+fn test_estimate_requests() -> Result<()> {
+    let server = MockServer::json(response_json(vec![]));
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let text = "One two three. ".repeat(10);
+    assert_eq!(5, client.estimate_requests(&text, 30));
+    assert_eq!(1, client.estimate_requests("", 30));
+    assert!(server.requests().is_empty());
+
+    client.chunk_strategy(ChunkStrategy::SentenceWithOverlap(1));
+    assert_eq!(9, client.estimate_requests(&text, 30));
+
+    client.check_chunked(&text, 30)?;
+    assert_eq!(9, server.requests().len());
+    Ok(())
+}
+
+#[test]
+fn test_lenient_matches() -> Result<()> {
+    let mut broken = match_json(10, 4, "BROKEN_RULE", "TYPOS");
+    broken.as_object_mut().unwrap().remove("rule");
+    let server = MockServer::json(response_json(vec![
+        match_json(0, 4, "RULE_A", "TYPOS"),
+        broken,
+        match_json(20, 2, "RULE_B", "GRAMMAR"),
+    ]));
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+    assert!(matches!(
+        client.check("text"),
+        Err(Error::InvalidJSON { .. })
+    ));
+
+    client.lenient_matches(true);
+    let response = client.check("text")?;
+    let rules: Vec<_> = response
+        .matches
+        .iter()
+        .map(|m| m.rule.id.as_str())
+        .collect();
+    assert_eq!(vec!["RULE_A", "RULE_B"], rules);
+    assert_eq!(1, response.skipped_matches());
+    Ok(())
+}
+
+#[test]
+fn test_as_curl() -> Result<()> {
+    let mut client = Client::new("secret-key");
+    client.header("X-Team", "O'Brien's docs")?;
+
+    let curl = client.as_curl("It's their car.");
+    assert!(curl.starts_with("curl -X GET 'http://api.grammarbot.io/v2/check?"));
+    assert!(curl.contains("api_key=REDACTED"));
+    assert!(!curl.contains("secret-key"));
+    assert!(curl.contains("text=It%27s+their+car."));
+    assert!(curl.contains(" -H 'x-team: O'\\''Brien'\\''s docs'"));
+    assert!(curl.contains(&format!(" -H 'user-agent: {}'", grammarbot::USER_AGENT)));
+
+    assert!(client
+        .as_curl_with("It's their car.", true)
+        .contains("api_key=secret-key"));
+
+    client
+        .header("Authorization", "Bearer gateway-secret")?
+        .header("Proxy-Authorization", "Basic proxy-secret")?
+        .header("Cookie", "session=cookie-secret")?;
+    let curl = client.as_curl("It's their car.");
+    for secret in &["gateway-secret", "proxy-secret", "cookie-secret"] {
+        assert!(!curl.contains(secret), "{} leaked in {}", secret, curl);
+    }
+    assert!(curl.contains(" -H 'authorization: REDACTED'"));
+    assert!(curl.contains(" -H 'x-team: O'\\''Brien'\\''s docs'"));
+
+    let curl = client.as_curl_with("It's their car.", true);
+    assert!(curl.contains(" -H 'authorization: Bearer gateway-secret'"));
+    Ok(())
+}
+
+#[test]
+fn test_base_path() -> Result<()> {
+    let server = MockServer::json(response_json(vec![]));
+    let mut client = Client::new("test");
+    for base in &["/api", "/api/"] {
+        client.base(&format!("{}{}", server.url(), base))?;
+        client.check("text")?;
+    }
+    client.base(server.url())?;
+    client.check("text")?;
+
+    let paths: Vec<_> = server.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(vec!["/api/v2/check", "/api/v2/check", "/v2/check"], paths);
+    Ok(())
+}
+
+#[test]
+fn test_check_chunked_with_deadline() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let text = request.param("text").unwrap();
+        let reply = Reply::json(&response_json(vec![match_json(0, 5, "RULE", "STYLE")]));
+        if text.starts_with("First") {
+            reply
+        } else {
+            reply.delay(Duration::from_millis(300))
+        }
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?;
+
+    let text = "First one. Second one. Third one.";
+    let (response, complete) =
+        client.check_chunked_with_deadline(text, 12, Duration::from_millis(150))?;
+    assert!(!complete);
+    assert_eq!(2, server.requests().len());
+    let offsets: Vec<_> = response.matches.iter().map(|m| m.offset).collect();
+    assert_eq!(vec![0, 11], offsets);
+
+    let (response, complete) =
+        client.check_chunked_with_deadline(text, 12, Duration::from_secs(10))?;
+    assert!(complete);
+    assert_eq!(3, response.matches.len());
+    Ok(())
+}
+
+#[test]
+fn test_unsupported_language() -> Result<()> {
+    let server = MockServer::start(|_| {
+        Reply::status(400).body(
+            "Error: 'xx-YY' is not a language code known to LanguageTool. \
+             Supported languages: ar, ast-ES, de-DE, en-US, fr.\n",
+        )
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?.language("xx-YY");
+    match client.check("text") {
+        Err(Error::UnsupportedLanguage {
+            requested,
+            supported,
+        }) => {
+            assert_eq!("xx-YY", requested);
+            assert_eq!(vec!["ar", "ast-ES", "de-DE", "en-US", "fr"], supported);
+        }
+        other => panic!("expected UnsupportedLanguage, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_sanitize_control_chars() -> Result<()> {
+    let server = MockServer::start(|request| {
+        let text = request.param("text").unwrap();
+        let offset = text.find("their").unwrap() as u32;
+        Reply::json(&response_json(vec![match_json(
+            offset, 5, "RULE", "GRAMMAR",
+        )]))
+    });
+    let mut client = Client::new("test");
+    client.base(server.url())?.sanitize_control_chars(true);
+
+    let text = "I can't\u{0}\u{7f}remember\r\nhow\tto go their.";
+    let response = client.check(text)?;
+    assert_eq!(
+        Some("I can't  remember \nhow\tto go their."),
+        server.requests()[0].param("text")
+    );
+    let span = response.matches[0].byte_range(text).unwrap();
+    assert_eq!("their", &text[span]);
+    Ok(())
+}
+
+#[test]
+fn test_category_priority() -> Result<()> {
+    let server = MockServer::json(response_json(vec![
+        match_json(0, 3, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(5, 3, "PASSIVE_VOICE", "STYLE"),
+        match_json(10, 2, "EN_A_VS_AN", "GRAMMAR"),
+        match_json(15, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(20, 2, "AGREEMENT", "GRAMMAR"),
+    ]));
+    let mut client = Client::new("test");
+    client
+        .base(server.url())?
+        .category_priority(&["GRAMMAR", "TYPOS"]);
+
+    let response = client.check("text")?;
+    let order: Vec<_> = response
+        .sorted_by_priority()
+        .iter()
+        .map(|m| (m.rule.category.id.as_str(), m.offset))
+        .collect();
+    assert_eq!(
+        vec![
+            ("GRAMMAR", 10),
+            ("GRAMMAR", 20),
+            ("TYPOS", 0),
+            ("TYPOS", 15),
+            ("STYLE", 5),
+        ],
+        order
+    );
+    Ok(())
+}
+
+#[test]
+fn test_clone_with_language() -> Result<()> {
+    let transport = FakeTransport::json(response_json(vec![]));
+    let mut english = Client::new("test");
+    english.transport(transport.clone());
+    let german = english.clone_with_language("de-DE");
+    english.check("text")?;
+    german.check("text")?;
+
+    // Both requests went through the transport set on `english` only.
+    let languages: Vec<_> = transport
+        .requests()
+        .iter()
+        .map(|request| request.param("language").unwrap().to_string())
+        .collect();
+    assert_eq!(vec!["en-US", "de-DE"], languages);
+    Ok(())
+}
@@ -0,0 +1,323 @@
+#![allow(dead_code)]
+
+use grammarbot::transport::{RawResponse, Transport};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// A request received by a [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    /// The first value of a query parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The first value of a header, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A canned reply from a [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct Reply {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub delay: Option<Duration>,
+}
+
+impl Reply {
+    pub fn json(value: &Value) -> Self {
+        Self::status(200)
+            .header("Content-Type", "application/json")
+            .body(value.to_string())
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            delay: None,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+/// A minimal HTTP/1.1 server answering every request with a handler.
+pub struct MockServer {
+    url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    pub fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> Reply + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler = Arc::new(handler);
+        let log = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                let handler = Arc::clone(&handler);
+                let log = Arc::clone(&log);
+                thread::spawn(move || serve(stream, &*handler, &log));
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// A server answering every request with the same JSON.
+    pub fn json(value: Value) -> Self {
+        Self::start(move |_| Reply::json(&value))
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// A request sent through a [`FakeTransport`].
+#[derive(Debug, Clone)]
+pub struct FakeRequest {
+    pub method: reqwest::Method,
+    pub url: reqwest::Url,
+    pub query: Vec<(String, String)>,
+    pub timeout: Option<Duration>,
+}
+
+impl FakeRequest {
+    /// The first value of a query parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// An in-process transport answering every request with the same JSON.
+///
+/// Clones share the recorded requests, so a clone can be given
+/// to a client and the requests read from the original.
+#[derive(Clone)]
+pub struct FakeTransport {
+    body: String,
+    requests: Arc<Mutex<Vec<FakeRequest>>>,
+}
+
+impl FakeTransport {
+    pub fn json(value: Value) -> Self {
+        Self {
+            body: value.to_string(),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn requests(&self) -> Vec<FakeRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for FakeTransport {
+    fn send(
+        &self,
+        method: reqwest::Method,
+        url: &reqwest::Url,
+        query: &[(&str, String)],
+        _headers: &reqwest::header::HeaderMap,
+        body: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> grammarbot::Result<RawResponse> {
+        assert_eq!(None, body);
+        self.requests.lock().unwrap().push(FakeRequest {
+            method,
+            url: url.clone(),
+            query: query
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+            timeout,
+        });
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            "application/json".parse().unwrap(),
+        );
+        Ok(RawResponse {
+            status: reqwest::StatusCode::OK,
+            headers,
+            body: self.body.clone(),
+        })
+    }
+}
+
+fn serve<F>(stream: TcpStream, handler: &F, log: &Mutex<Vec<Request>>)
+where
+    F: Fn(&Request) -> Reply,
+{
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut line = String::new();
+    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+        return;
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    let length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+
+    let url = reqwest::Url::parse(&format!("http://localhost{}", target)).unwrap();
+    let request = Request {
+        method,
+        path: url.path().to_string(),
+        query: url.query_pairs().into_owned().collect(),
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    };
+    let reply = handler(&request);
+    log.lock().unwrap().push(request);
+
+    if let Some(delay) = reply.delay {
+        thread::sleep(delay);
+    }
+    let mut stream = stream;
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        reply.status,
+        reply.body.len()
+    );
+    for (name, value) in &reply.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(&reply.body);
+}
+
+/// A match as the API would return it.
+pub fn match_json(offset: u32, length: u32, rule_id: &str, category: &str) -> Value {
+    json!({
+        "message": format!("{} message", rule_id),
+        "shortMessage": "",
+        "replacements": [],
+        "offset": offset,
+        "length": length,
+        "context": { "text": "", "offset": offset, "length": length },
+        "sentence": "",
+        "type": { "typeName": "Other" },
+        "rule": {
+            "id": rule_id,
+            "description": format!("{} description", rule_id),
+            "issueType": "uncategorized",
+            "category": { "id": category, "name": category }
+        }
+    })
+}
+
+/// A full response with the given matches.
+pub fn response_json(matches: Vec<Value>) -> Value {
+    response_json_detected(matches, "English (US)", "en-US")
+}
+
+/// A full response with the given matches and detected language.
+pub fn response_json_detected(matches: Vec<Value>, name: &str, code: &str) -> Value {
+    json!({
+        "software": {
+            "name": "GrammarBot",
+            "version": "4.3.1",
+            "apiVersion": 1,
+            "premium": false,
+            "premiumHint": "You might be missing errors only the Premium version can find.",
+            "status": ""
+        },
+        "warnings": { "incompleteResults": false },
+        "language": {
+            "name": name,
+            "code": code,
+            "detectedLanguage": { "name": name, "code": code }
+        },
+        "matches": matches
+    })
+}
+
+/// Deserialize a fixture into a [`grammarbot::Response`].
+pub fn response(value: Value) -> grammarbot::Response {
+    serde_json::from_value(value).unwrap()
+}
+
+/// Set the replacement values of a match fixture.
+pub fn with_replacements(mut value: Value, replacements: &[&str]) -> Value {
+    value["replacements"] = replacements
+        .iter()
+        .map(|replacement| json!({ "value": replacement }))
+        .collect();
+    value
+}
+
+/// Deserialize a single match fixture.
+pub fn single_match(value: Value) -> grammarbot::types::Match {
+    response(response_json(vec![value])).matches.remove(0)
+}
@@ -0,0 +1,73 @@
+//! Tests changing the process environment, kept apart from the tests
+//! in `client.rs`, and run one at a time.
+extern crate grammarbot;
+mod common;
+
+use common::{response_json, MockServer};
+use grammarbot::{Client, Error, Result};
+use std::sync::{Mutex, MutexGuard};
+
+static ENV: Mutex<()> = Mutex::new(());
+
+/// Hold the environment for the rest of a test.
+fn lock_env() -> MutexGuard<'static, ()> {
+    ENV.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[test]
+fn test_default_headers_from_env() -> Result<()> {
+    let _env = lock_env();
+    std::env::set_var("GRAMMARBOT_HEADER_X_GATEWAY_TOKEN", "secret");
+    let server = MockServer::json(response_json(vec![]));
+    let mut client = Client::new("test");
+    client.base(server.url())?.default_headers_from_env()?;
+    std::env::remove_var("GRAMMARBOT_HEADER_X_GATEWAY_TOKEN");
+    client.check("text")?;
+    assert_eq!(
+        Some("secret"),
+        server.requests()[0].header("X-Gateway-Token")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_as_curl_redacts_env_headers() -> Result<()> {
+    let _env = lock_env();
+    std::env::set_var("GRAMMARBOT_HEADER_X_CURL_TOKEN", "env-secret");
+    let mut client = Client::new("test");
+    client.default_headers_from_env()?;
+    std::env::remove_var("GRAMMARBOT_HEADER_X_CURL_TOKEN");
+
+    let curl = client.as_curl("text");
+    assert!(!curl.contains("env-secret"));
+    assert!(curl.contains(" -H 'x-curl-token: REDACTED'"));
+    assert!(client
+        .as_curl_with("text", true)
+        .contains(" -H 'x-curl-token: env-secret'"));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_default_headers_from_env_not_unicode() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let _env = lock_env();
+    std::env::set_var("GRAMMARBOT_HEADER_A_FIRST", "fine");
+    std::env::set_var("GRAMMARBOT_HEADER_X_BAD", OsStr::from_bytes(b"bad\xff"));
+    std::env::set_var("UNRELATED_NOT_UNICODE", OsStr::from_bytes(b"\xfe"));
+    let mut client = Client::new("test");
+    match client.default_headers_from_env() {
+        Err(Error::InvalidHeader { name }) => assert_eq!("x-bad", name),
+        Err(other) => panic!("expected InvalidHeader, got {:?}", other),
+        Ok(_) => panic!("expected InvalidHeader"),
+    }
+    assert!(!client.as_curl("text").contains("a-first"));
+
+    std::env::remove_var("GRAMMARBOT_HEADER_X_BAD");
+    client.default_headers_from_env().unwrap();
+    std::env::remove_var("GRAMMARBOT_HEADER_A_FIRST");
+    std::env::remove_var("UNRELATED_NOT_UNICODE");
+    assert!(client.as_curl("text").contains("a-first"));
+}
@@ -0,0 +1,534 @@
+extern crate grammarbot;
+mod common;
+
+use common::{match_json, response, response_json, single_match, with_replacements};
+use grammarbot::offset::Location;
+use grammarbot::{CodeAction, Segment, Severity};
+use serde_json::json;
+
+#[test]
+fn test_worst_matches() {
+    let response = response(response_json(vec![
+        match_json(0, 3, "WHITESPACE_RULE", "TYPOGRAPHY"),
+        match_json(10, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        match_json(20, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(5, 2, "EN_A_VS_AN", "GRAMMAR"),
+    ]));
+    let worst = response.worst_matches(2);
+    assert_eq!(2, worst.len());
+    assert_eq!(Severity::Spelling, worst[0].severity());
+    assert_eq!(20, worst[0].offset);
+    assert_eq!(Severity::Grammar, worst[1].severity());
+    assert_eq!(5, worst[1].offset);
+    assert_eq!(20, response.worst_match().unwrap().offset);
+}
+
+#[test]
+fn test_rule_catalog() {
+    let response = response(response_json(vec![
+        match_json(0, 3, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(5, 2, "EN_A_VS_AN", "GRAMMAR"),
+        match_json(10, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+    ]));
+    let catalog = response.rule_catalog();
+    let ids: Vec<_> = catalog.keys().map(String::as_str).collect();
+    assert_eq!(vec!["EN_A_VS_AN", "MORFOLOGIK_RULE_EN_US"], ids);
+    let rule = &catalog["MORFOLOGIK_RULE_EN_US"];
+    assert_eq!("MORFOLOGIK_RULE_EN_US description", rule.description);
+    assert_eq!("TYPOS", rule.category.id);
+}
+
+#[test]
+fn test_replacement_diff() {
+    let text = "I can't remember how to go their.";
+    let substitution = single_match(with_replacements(
+        match_json(27, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        &["there"],
+    ));
+    assert_eq!(
+        Some("[-their-]{+there+}".to_string()),
+        substitution.replacement_diff(text)
+    );
+
+    let insertion = single_match(with_replacements(
+        match_json(7, 0, "COMMA_RULE", "PUNCTUATION"),
+        &[","],
+    ));
+    assert_eq!(Some("{+,+}".to_string()), insertion.replacement_diff(text));
+
+    let deletion = single_match(with_replacements(
+        match_json(26, 6, "ENGLISH_WORD_REPEAT_RULE", "MISC"),
+        &[""],
+    ));
+    assert_eq!(
+        Some("[- their-]".to_string()),
+        deletion.replacement_diff(text)
+    );
+}
+
+#[test]
+fn test_to_markdown_report() {
+    let text = "I can't remember\nhow to go their.";
+    let response = response(response_json(vec![
+        match_json(2, 5, "CANT_RULE", "TYPOGRAPHY"),
+        with_replacements(
+            match_json(27, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+            &["there"],
+        ),
+    ]));
+    let report = response.to_markdown_report(text);
+    assert!(report.starts_with("## GrammarBot: 2 issues found\n"));
+    assert!(report.contains("| 1:3 | `CANT_RULE` | CANT_RULE message |  |\n"));
+    assert!(report.contains("| 2:11 | `CONFUSION_RULE` | CONFUSION_RULE message | `there` |\n"));
+    assert_eq!(
+        4,
+        report.lines().filter(|line| line.starts_with('|')).count()
+    );
+}
+
+#[test]
+fn test_display_message() {
+    let mut value = match_json(27, 5, "CONFUSION_RULE", "CONFUSED_WORDS");
+    assert_eq!(
+        "CONFUSION_RULE message",
+        single_match(value.clone()).display_message()
+    );
+    value["shortMessage"] = "Confused word".into();
+    assert_eq!("Confused word", single_match(value).display_message());
+}
+
+#[test]
+fn test_rule_is_premium() {
+    let premium = single_match(match_json(0, 4, "EN_REDUNDANCY_PREMIUM", "REDUNDANCY"));
+    let regular = single_match(match_json(0, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"));
+    assert!(premium.rule.is_premium());
+    assert!(!regular.rule.is_premium());
+    assert!(regular.rule.is_premium_with(&["morfologik"]));
+}
+
+#[test]
+fn test_apply_corrections_selective() {
+    let text = "I cant remember how to go their.";
+    let response = response(response_json(vec![
+        with_replacements(
+            match_json(2, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+            &["can't"],
+        ),
+        with_replacements(
+            match_json(26, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+            &["there"],
+        ),
+    ]));
+    let fixed = response.apply_corrections_selective(text, |m| m.severity() == Severity::Spelling);
+    assert_eq!("I can't remember how to go their.", fixed);
+    assert_eq!(
+        "I can't remember how to go there.",
+        response.apply_corrections(text)
+    );
+}
+
+#[test]
+fn test_counts_by_rule() {
+    let response = response(response_json(vec![
+        match_json(0, 4, "PASSIVE_VOICE", "STYLE"),
+        match_json(10, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(20, 4, "PASSIVE_VOICE", "STYLE"),
+    ]));
+    let counts = response.counts_by_rule();
+    assert_eq!(2, counts.len());
+    assert_eq!(2, counts["PASSIVE_VOICE"]);
+    assert_eq!(1, counts["MORFOLOGIK_RULE_EN_US"]);
+}
+
+#[test]
+fn test_match_ord() {
+    let mut matches = response(response_json(vec![
+        match_json(10, 4, "B_RULE", "TYPOS"),
+        match_json(10, 2, "B_RULE", "TYPOS"),
+        match_json(0, 4, "C_RULE", "TYPOS"),
+        match_json(10, 4, "A_RULE", "TYPOS"),
+    ]))
+    .matches;
+    matches.sort();
+    let sorted: Vec<_> = matches
+        .iter()
+        .map(|m| (m.offset, m.length, m.rule.id.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (0, 4, "C_RULE"),
+            (10, 2, "B_RULE"),
+            (10, 4, "A_RULE"),
+            (10, 4, "B_RULE"),
+        ],
+        sorted
+    );
+}
+
+#[test]
+fn test_context_map() {
+    let text = "I can't remember how to go their.";
+    let response = response(response_json(vec![
+        match_json(27, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        match_json(24, 8, "GO_THEIR", "GRAMMAR"),
+        match_json(2, 5, "CANT_RULE", "TYPOGRAPHY"),
+    ]));
+    let segments = response.context_map(text);
+    assert_eq!(text, segments.iter().map(Segment::text).collect::<String>());
+    let flagged: Vec<_> = segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Flagged { text, match_ref } => Some((*text, match_ref.rule.id.as_str())),
+            Segment::Plain(_) => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![("can't", "CANT_RULE"), ("go their", "GO_THEIR")],
+        flagged
+    );
+}
+
+#[test]
+fn test_to_code_action() {
+    let text = "I can't remember how to go their.";
+    let m = single_match(with_replacements(
+        match_json(27, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        &["there", "they're"],
+    ));
+    let actions = m.to_code_action(text);
+    assert_eq!(
+        vec![
+            CodeAction {
+                title: "Replace 'their' with 'there'".to_string(),
+                range: 27..32,
+                replacement: "there".to_string(),
+            },
+            CodeAction {
+                title: "Replace 'their' with 'they're'".to_string(),
+                range: 27..32,
+                replacement: "they're".to_string(),
+            },
+        ],
+        actions
+    );
+}
+
+#[test]
+fn test_content_hash() {
+    let matches = || {
+        vec![
+            with_replacements(match_json(0, 4, "RULE_A", "TYPOS"), &["fix"]),
+            match_json(10, 2, "RULE_B", "GRAMMAR"),
+        ]
+    };
+    let first = response(response_json(matches()));
+    let mut value = response_json(matches().into_iter().rev().collect());
+    value["software"]["premiumHint"] = "Something else entirely.".into();
+    let second = response(value);
+    assert_eq!(first.content_hash(), second.content_hash());
+
+    let different = response(response_json(vec![match_json(0, 4, "RULE_A", "TYPOS")]));
+    assert_ne!(first.content_hash(), different.content_hash());
+    let empty_replacement = response(response_json(vec![with_replacements(
+        match_json(0, 4, "RULE_A", "TYPOS"),
+        &[""],
+    )]));
+    assert_ne!(different.content_hash(), empty_replacement.content_hash());
+
+    // Persisted hashes must keep matching across builds.
+    assert_eq!(0x24e4_6b3b_e2ba_6123, first.content_hash());
+}
+
+#[test]
+fn test_min_confidence() {
+    let with_confidence = |offset, rule_id, confidences: &[f64]| {
+        let mut value = with_replacements(
+            match_json(offset, 3, rule_id, "TYPOS"),
+            &vec!["fix"; confidences.len()],
+        );
+        for (replacement, confidence) in confidences.iter().enumerate() {
+            value["replacements"][replacement]["confidence"] = json!(confidence);
+        }
+        value
+    };
+    let matches = || {
+        vec![
+            with_confidence(0, "HIGH", &[0.2, 0.9]),
+            with_confidence(5, "LOW", &[0.3]),
+            with_confidence(10, "UNKNOWN", &[]),
+        ]
+    };
+    let rule_ids = |response: &grammarbot::Response| -> Vec<String> {
+        response.matches.iter().map(|m| m.rule.id.clone()).collect()
+    };
+
+    let mut lenient = response(response_json(matches()));
+    lenient.min_confidence(0.5);
+    assert_eq!(vec!["HIGH", "UNKNOWN"], rule_ids(&lenient));
+
+    let mut strict = response(response_json(matches()));
+    strict.min_confidence_with(0.5, false);
+    assert_eq!(vec!["HIGH"], rule_ids(&strict));
+}
+
+#[test]
+fn test_index() {
+    let response = response(response_json(vec![
+        match_json(0, 4, "RULE_A", "TYPOS"),
+        match_json(10, 2, "RULE_B", "GRAMMAR"),
+    ]));
+    assert_eq!("RULE_A", response[0].rule.id);
+    assert_eq!("RULE_B", response[1].rule.id);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_index_out_of_range() {
+    let response = response(response_json(vec![match_json(0, 4, "RULE_A", "TYPOS")]));
+    let _ = &response[1];
+}
+
+#[test]
+fn test_dedup_replacements() {
+    let mut m = single_match(with_replacements(
+        match_json(0, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        &["there", "There", "there", "they're", "there"],
+    ));
+    m.dedup_replacements();
+    let values: Vec<_> = m.replacements.iter().map(|r| r.value.as_str()).collect();
+    assert_eq!(vec!["there", "There", "they're"], values);
+}
+
+#[test]
+fn test_matches_by_line() {
+    let text = "I can't remember\nhow to go their. Its fine.";
+    let response = response(response_json(vec![
+        match_json(27, 3, "ITS_RULE", "GRAMMAR"),
+        match_json(2, 5, "CONTRACTION_RULE", "STYLE"),
+        match_json(27, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        match_json(10, 12, "SPANNING_RULE", "STYLE"),
+    ]));
+    let by_line: Vec<(usize, Vec<&str>)> = response
+        .matches_by_line(text)
+        .into_iter()
+        .map(|(line, matches)| (line, matches.iter().map(|m| m.rule.id.as_str()).collect()))
+        .collect();
+    assert_eq!(
+        vec![
+            (1, vec!["CONTRACTION_RULE", "SPANNING_RULE"]),
+            (2, vec!["ITS_RULE", "CONFUSION_RULE"]),
+        ],
+        by_line
+    );
+}
+
+#[test]
+fn test_only_fixable() {
+    let response = response(response_json(vec![
+        with_replacements(match_json(0, 4, "FIXABLE_A", "TYPOS"), &["fix"]),
+        match_json(5, 3, "FLAG_ONLY", "STYLE"),
+        with_replacements(match_json(10, 2, "FIXABLE_B", "GRAMMAR"), &["a", "b"]),
+    ]));
+    let fixable: Vec<_> = response
+        .only_fixable()
+        .iter()
+        .map(|m| m.rule.id.as_str())
+        .collect();
+    assert_eq!(vec!["FIXABLE_A", "FIXABLE_B"], fixable);
+}
+
+#[test]
+fn test_suggested_sentence() {
+    let sentence = "I can't remember how to go their.";
+    let mut value = with_replacements(
+        match_json(27, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        &["there"],
+    );
+    value["sentence"] = sentence.into();
+    value["context"]["text"] = format!("...ay. {}", sentence).into();
+    value["context"]["offset"] = 34.into();
+    assert_eq!(
+        "I can't remember how to go there.",
+        single_match(value.clone()).suggested_sentence()
+    );
+
+    value["replacements"] = json!([]);
+    assert_eq!(sentence, single_match(value).suggested_sentence());
+
+    let sentence = "I saw their car at their house.";
+    let mut value = with_replacements(
+        match_json(19, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        &["there"],
+    );
+    value["sentence"] = sentence.into();
+    value["context"]["text"] = sentence.into();
+    assert_eq!(
+        "I saw their car at there house.",
+        single_match(value).suggested_sentence()
+    );
+}
+
+#[test]
+fn test_into_matches() {
+    let response = response(response_json(vec![
+        match_json(0, 4, "RULE_A", "TYPOS"),
+        match_json(10, 2, "RULE_B", "GRAMMAR"),
+    ]));
+    let matches: Vec<grammarbot::types::Match> = response.into_matches();
+    let rules: Vec<_> = matches.into_iter().map(|m| m.rule.id).collect();
+    assert_eq!(vec!["RULE_A", "RULE_B"], rules);
+}
+
+#[test]
+fn test_matches_sorted_by_severity() {
+    let response = response(response_json(vec![
+        match_json(0, 3, "WHITESPACE_RULE", "TYPOGRAPHY"),
+        match_json(30, 5, "PASSIVE_VOICE", "STYLE"),
+        match_json(10, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        match_json(20, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(5, 2, "EN_A_VS_AN", "GRAMMAR"),
+    ]));
+    let sorted: Vec<_> = response
+        .matches_sorted_by_severity()
+        .iter()
+        .map(|m| (m.severity(), m.offset))
+        .collect();
+    assert_eq!(
+        vec![
+            (Severity::Spelling, 20),
+            (Severity::Grammar, 5),
+            (Severity::Grammar, 10),
+            (Severity::Style, 30),
+            (Severity::Other, 0),
+        ],
+        sorted
+    );
+    assert_eq!(0, response.matches[0].offset);
+}
+
+#[test]
+fn test_serialize_skips_empty_fields() {
+    let mut value = with_replacements(
+        match_json(27, 5, "CONFUSION_RULE", "CONFUSED_WORDS"),
+        &["there"],
+    );
+    value["replacements"][0]["confidence"] = json!(0.9);
+    let original = response(response_json(vec![
+        value,
+        match_json(0, 1, "UPPERCASE_SENTENCE_START", "CASING"),
+    ]));
+
+    let serialized = serde_json::to_value(&original).unwrap();
+    let matches = &serialized["matches"];
+    assert!(matches[0].get("shortMessage").is_none());
+    assert_eq!(
+        json!([{ "value": "there", "confidence": 0.9 }]),
+        matches[0]["replacements"]
+    );
+    assert!(matches[1].get("replacements").is_none());
+    assert!(serialized["language"]["detectedLanguage"]
+        .get("confidence")
+        .is_none());
+    assert_eq!("CONFUSION_RULE", matches[0]["rule"]["id"]);
+
+    let round_trip = response(serialized.clone());
+    assert_eq!(serialized, serde_json::to_value(&round_trip).unwrap());
+    assert!(round_trip.matches[1].short_message.is_empty());
+}
+
+#[test]
+fn test_location() {
+    let text = "Héllo wörld.\nI can't remember how to go 😀 their.";
+    let m = single_match(match_json(43, 5, "CONFUSION_RULE", "CONFUSED_WORDS"));
+    assert_eq!(
+        Some(Location {
+            line: 2,
+            column: 30,
+            byte_range: 47..52,
+            utf16_range: 43..48,
+        }),
+        m.location(text)
+    );
+    assert_eq!("their", &text[47..52]);
+
+    let outside = single_match(match_json(100, 5, "CONFUSION_RULE", "CONFUSED_WORDS"));
+    assert_eq!(None, outside.location(text));
+}
+
+#[test]
+fn test_worst_category() {
+    let typos = response(response_json(vec![
+        match_json(0, 3, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(5, 2, "EN_A_VS_AN", "GRAMMAR"),
+        match_json(10, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(20, 5, "PASSIVE_VOICE", "STYLE"),
+    ]));
+    assert_eq!(Some("TYPOS"), typos.worst_category());
+    assert_eq!(Some(&2), typos.counts_by_category().get("TYPOS"));
+
+    let tied = response(response_json(vec![
+        match_json(0, 3, "PASSIVE_VOICE", "STYLE"),
+        match_json(5, 2, "EN_A_VS_AN", "GRAMMAR"),
+        match_json(10, 4, "WORDINESS", "REDUNDANCY"),
+    ]));
+    assert_eq!(Some("GRAMMAR"), tied.worst_category());
+
+    let alphabetical = response(response_json(vec![
+        match_json(0, 3, "PASSIVE_VOICE", "STYLE"),
+        match_json(10, 4, "WORDINESS", "REDUNDANCY"),
+    ]));
+    assert_eq!(Some("REDUNDANCY"), alphabetical.worst_category());
+
+    assert_eq!(None, response(response_json(vec![])).worst_category());
+}
+
+#[test]
+fn test_is_significant() {
+    let style = response(response_json(vec![
+        match_json(0, 5, "PASSIVE_VOICE", "STYLE"),
+        match_json(10, 4, "WORDINESS", "REDUNDANCY"),
+    ]));
+    assert!(!style.is_significant(Severity::Grammar));
+    assert!(style.is_significant(Severity::Style));
+
+    let typo = response(response_json(vec![match_json(
+        0,
+        3,
+        "MORFOLOGIK_RULE_EN_US",
+        "TYPOS",
+    )]));
+    assert!(typo.is_significant(Severity::Grammar));
+    assert!(!response(response_json(vec![])).is_significant(Severity::Other));
+}
+
+#[test]
+fn test_stats_by_severity() {
+    let mixed = response(response_json(vec![
+        match_json(0, 3, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(5, 2, "EN_A_VS_AN", "GRAMMAR"),
+        match_json(8, 2, "YOUR_YOU_RE", "CONFUSED_WORDS"),
+        match_json(10, 4, "MORFOLOGIK_RULE_EN_US", "TYPOS"),
+        match_json(15, 4, "UPPERCASE_SENTENCE_START", "CASING"),
+        match_json(20, 5, "PASSIVE_VOICE", "STYLE"),
+        match_json(26, 1, "WHITESPACE_RULE", "TYPOGRAPHY"),
+    ]));
+    assert_eq!([2, 3, 1, 1], mixed.stats_by_severity());
+    assert_eq!([0; 4], response(response_json(vec![])).stats_by_severity());
+}
+
+#[test]
+fn test_byte_range_in_surrogate_pair() {
+    let text = "Go 😀 there.";
+    let span = |offset, length| {
+        single_match(match_json(offset, length, "RULE", "STYLE"))
+            .byte_range(text)
+            .map(|range| &text[range])
+    };
+    assert_eq!(Some("😀"), span(3, 2));
+    assert_eq!(Some("😀"), span(4, 1));
+    assert_eq!(Some("😀"), span(3, 1));
+    assert_eq!(Some("😀 "), span(4, 2));
+    assert_eq!(Some("😀"), span(4, 0));
+    assert_eq!(Some("there."), span(6, 6));
+    assert_eq!(None, span(12, 1));
+}